@@ -0,0 +1,183 @@
+use std::path::{Path, PathBuf};
+
+use clap::{Args, Subcommand};
+use colored::*;
+use serde_yaml::Value;
+use crate::configs::discovery::locate_project;
+use crate::configs::format::{read_value, ConfigFormat};
+use crate::configs::loader::{resolve_project, ResolvedConfig};
+use crate::configs::merge::{is_named_list, item_name};
+use crate::error::{Result, ToolError};
+
+#[derive(Debug, Args, Clone)]
+pub struct ConfigArgs {
+    #[command(subcommand)]
+    pub action: ConfigAction,
+}
+
+#[derive(Debug, Subcommand, Clone)]
+pub enum ConfigAction {
+    /// Print the project configuration
+    Show(ShowArgs),
+    /// Convert the project config to another format
+    Convert(ConvertArgs),
+}
+
+#[derive(Debug, Args, Clone)]
+pub struct ShowArgs {
+    /// Project name or directory (defaults to the nearest config above the current directory)
+    pub name: Option<String>,
+
+    /// Print the effective config after includes, profile and local overrides
+    #[arg(long)]
+    pub resolved: bool,
+
+    /// Profile to apply when resolving
+    #[arg(long)]
+    pub profile: Option<String>,
+}
+
+#[derive(Debug, Args, Clone)]
+pub struct ConvertArgs {
+    /// Project name or directory (defaults to the nearest config above the current directory)
+    pub name: Option<String>,
+
+    /// Target format
+    #[arg(long, value_enum)]
+    pub to: ConfigFormat,
+
+    /// Where to write the converted config (defaults to devspin.<format> next to the original)
+    #[arg(long)]
+    pub output: Option<String>,
+
+    /// Overwrite the output file if it already exists
+    #[arg(long)]
+    pub force: bool,
+}
+
+impl ConfigArgs {
+    pub async fn execute(&self) -> Result<()> {
+        match &self.action {
+            ConfigAction::Show(args) => args.execute(),
+            ConfigAction::Convert(args) => args.execute(),
+        }
+    }
+}
+
+impl ShowArgs {
+    fn execute(&self) -> Result<()> {
+        let path = locate_project(self.name.as_deref(), None)?;
+
+        if !self.resolved {
+            if self.profile.is_some() {
+                return Err(ToolError::ConfigError("--profile requires --resolved".to_string()));
+            }
+            print!("{}", std::fs::read_to_string(&path)?);
+            return Ok(());
+        }
+
+        let resolved = resolve_project(&path, self.profile.as_deref())?;
+
+        println!("{}", "# Resolved configuration".dimmed());
+        for source in &resolved.sources {
+            println!("{}", format!("#   merged: {}", source.display()).dimmed());
+        }
+        println!();
+
+        let mut lines = Vec::new();
+        if let Value::Mapping(_) = &resolved.value {
+            render_value(&resolved, &resolved.value, "", 0, &mut lines);
+        }
+        for line in lines {
+            println!("{}", line);
+        }
+
+        Ok(())
+    }
+}
+
+impl ConvertArgs {
+    fn execute(&self) -> Result<()> {
+        let source = locate_project(self.name.as_deref(), None)?;
+        let source_format = ConfigFormat::from_path(&source)?;
+
+        if source_format == self.to && self.output.is_none() {
+            return Err(ToolError::ConfigError(format!(
+                "{} is already {}", source.display(), self.to.extension()
+            )));
+        }
+
+        let output = match &self.output {
+            Some(output) => PathBuf::from(output),
+            None => source
+                .parent()
+                .unwrap_or_else(|| Path::new("."))
+                .join(format!("devspin.{}", self.to.extension())),
+        };
+
+        if output.exists() && !self.force {
+            return Err(ToolError::ConfigError(format!(
+                "{} already exists (use --force to overwrite)", output.display()
+            )));
+        }
+
+        // Convert the file as written, keeping includes and profiles intact
+        let value = read_value(&source)?;
+        std::fs::write(&output, self.to.serialize(&value)?)?;
+
+        println!("{} {}", "✓".green(), format!("Converted {} → {}", source.display(), output.display()).bold());
+        if output.parent() == source.parent() {
+            println!("{}", format!("Remove {} so devspin picks up the new file", source.display()).dimmed());
+        }
+        Ok(())
+    }
+}
+
+/// Renders `value` as YAML, annotating each leaf with the file it came from.
+fn render_value(resolved: &ResolvedConfig, value: &Value, key: &str, indent: usize, lines: &mut Vec<String>) {
+    let pad = " ".repeat(indent);
+
+    let Value::Mapping(mapping) = value else { return };
+    for (child_key, child) in mapping {
+        let Some(child_key) = child_key.as_str() else { continue };
+        let child_path = if key.is_empty() {
+            child_key.to_string()
+        } else {
+            format!("{}.{}", key, child_key)
+        };
+
+        match child {
+            Value::Mapping(children) if !children.is_empty() => {
+                lines.push(format!("{}{}:", pad, child_key));
+                render_value(resolved, child, &child_path, indent + 2, lines);
+            }
+            Value::Sequence(items) if !items.is_empty() && is_named_list(items) => {
+                lines.push(format!("{}{}:", pad, child_key));
+                for item in items {
+                    let item_path = format!("{}[{}]", child_path, item_name(item).unwrap_or_default());
+                    let mut item_lines = Vec::new();
+                    render_value(resolved, item, &item_path, indent + 4, &mut item_lines);
+                    if let Some(first) = item_lines.first_mut() {
+                        *first = format!("{}  - {}", pad, first.trim_start());
+                    }
+                    lines.extend(item_lines);
+                }
+            }
+            _ => {
+                let origin = resolved.origin_of(&child_path).unwrap_or("default");
+                lines.push(format!(
+                    "{}{}: {}  {}",
+                    pad,
+                    child_key,
+                    render_leaf(child),
+                    format!("# {}", origin).dimmed()
+                ));
+            }
+        }
+    }
+}
+
+fn render_leaf(value: &Value) -> String {
+    // JSON is valid YAML flow syntax and keeps strings unambiguous
+    serde_json::to_string(value).unwrap_or_else(|_| "~".to_string())
+}
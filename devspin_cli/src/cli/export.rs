@@ -0,0 +1,210 @@
+use std::path::{Path, PathBuf};
+
+use clap::{Args, Subcommand};
+use colored::*;
+use crate::configs::compose::to_compose;
+use crate::configs::discovery::locate_project;
+use crate::configs::export::Exported;
+use crate::configs::procfile::to_procfile;
+use crate::configs::systemd::{existing_units, to_systemd, user_unit_dir};
+use crate::configs::yaml_parser::ProjectConfig;
+use crate::error::{Result, ToolError};
+
+#[derive(Debug, Args, Clone)]
+pub struct ExportArgs {
+    #[command(subcommand)]
+    pub target: ExportTarget,
+}
+
+#[derive(Debug, Subcommand, Clone)]
+pub enum ExportTarget {
+    /// Write a docker-compose file for the project's services
+    Compose(ComposeExportArgs),
+    /// Write a Procfile for the project's processes
+    Procfile(ProcfileExportArgs),
+    /// Write systemd units that run the project's services
+    Systemd(SystemdExportArgs),
+}
+
+#[derive(Debug, Args, Clone)]
+pub struct ComposeExportArgs {
+    #[command(flatten)]
+    pub source: ExportSource,
+
+    #[command(flatten)]
+    pub output: ExportOutput,
+}
+
+#[derive(Debug, Args, Clone)]
+pub struct ProcfileExportArgs {
+    #[command(flatten)]
+    pub source: ExportSource,
+
+    #[command(flatten)]
+    pub output: ExportOutput,
+}
+
+#[derive(Debug, Args, Clone)]
+pub struct SystemdExportArgs {
+    #[command(flatten)]
+    pub source: ExportSource,
+
+    /// Write user units, run by `systemctl --user` (the only kind supported)
+    #[arg(long)]
+    pub user: bool,
+
+    /// Directory to write the units to (defaults to ~/.config/systemd/user)
+    #[arg(long)]
+    pub dir: Option<String>,
+
+    /// Overwrite units that already exist
+    #[arg(long)]
+    pub force: bool,
+}
+
+/// Which project to export.
+#[derive(Debug, Args, Clone)]
+pub struct ExportSource {
+    /// Project name or directory (defaults to the nearest config above the current directory)
+    #[arg(short = 'p', long)]
+    pub project: Option<String>,
+
+    /// Path to the config file, bypassing project lookup
+    #[arg(short = 'f', long)]
+    pub file: Option<String>,
+
+    /// Profile to merge on top of the base config
+    #[arg(long)]
+    pub profile: Option<String>,
+}
+
+/// Where an exported file goes.
+#[derive(Debug, Args, Clone)]
+pub struct ExportOutput {
+    /// Where to write the file, or - for stdout (defaults to next to the config)
+    #[arg(long)]
+    pub output: Option<String>,
+
+    /// Overwrite the output file if it already exists
+    #[arg(long)]
+    pub force: bool,
+}
+
+impl ExportArgs {
+    pub async fn execute(&self) -> Result<()> {
+        match &self.target {
+            ExportTarget::Compose(args) => args.execute(),
+            ExportTarget::Procfile(args) => args.execute(),
+            ExportTarget::Systemd(args) => args.execute(),
+        }
+    }
+}
+
+impl ComposeExportArgs {
+    fn execute(&self) -> Result<()> {
+        let (config_path, project) = self.source.load()?;
+        let exported = to_compose(&project)?;
+        self.output.write(&config_path, "docker-compose.yml", &exported)
+    }
+}
+
+impl ProcfileExportArgs {
+    fn execute(&self) -> Result<()> {
+        let (config_path, project) = self.source.load()?;
+        self.output.write(&config_path, "Procfile", &to_procfile(&project))
+    }
+}
+
+impl SystemdExportArgs {
+    fn execute(&self) -> Result<()> {
+        if !self.user {
+            return Err(ToolError::ConfigError(
+                "Only user units are supported; pass --user to write units for `systemctl --user`".to_string()
+            ));
+        }
+        let (_, project) = self.source.load()?;
+        let units = to_systemd(&project, &std::env::current_dir()?);
+
+        let dir = match &self.dir {
+            Some(dir) => PathBuf::from(dir),
+            None => user_unit_dir()
+                .ok_or_else(|| ToolError::ConfigError("Cannot find the user config directory; pass --dir".to_string()))?,
+        };
+        let existing = existing_units(&units, &dir);
+        if !existing.is_empty() && !self.force {
+            return Err(ToolError::ConfigError(format!(
+                "{} already exist in {} (use --force to overwrite)", existing.join(", "), dir.display()
+            )));
+        }
+
+        std::fs::create_dir_all(&dir)?;
+        for unit in &units.units {
+            std::fs::write(dir.join(&unit.name), &unit.content)?;
+        }
+        println!("{} {}", "✓".green(), format!("Wrote {} unit(s) to {}", units.units.len(), dir.display()).bold());
+        for unit in &units.units {
+            println!("  {}", unit.name.dimmed());
+        }
+
+        println!();
+        println!("Start them with:");
+        println!("  systemctl --user daemon-reload");
+        println!("  systemctl --user start {}", units.target);
+        println!("{}", format!("  (systemctl --user enable {} starts them at login)", units.target).dimmed());
+
+        print_unmapped(&units.unmapped);
+        Ok(())
+    }
+}
+
+impl ExportSource {
+    fn load(&self) -> Result<(PathBuf, ProjectConfig)> {
+        if self.project.is_some() && self.file.is_some() {
+            return Err(ToolError::ConfigError("Cannot use both a project name and --file".to_string()));
+        }
+        let config_path = locate_project(self.project.as_deref(), self.file.as_deref())?;
+        let project = ProjectConfig::load(&config_path.to_string_lossy(), self.profile.as_deref())?;
+        Ok((config_path, project))
+    }
+}
+
+impl ExportOutput {
+    /// Writes the file (to `default_name` next to the config unless told
+    /// otherwise) and reports what could not be exported.
+    fn write(&self, config_path: &Path, default_name: &str, exported: &Exported) -> Result<()> {
+        if self.output.as_deref() == Some("-") {
+            print!("{}", exported.content);
+            // Keep stdout clean for redirection
+            for item in &exported.unmapped {
+                eprintln!("{} {}", "WARN:".yellow(), item);
+            }
+            return Ok(());
+        }
+
+        let output = match &self.output {
+            Some(output) => PathBuf::from(output),
+            None => config_path.parent().unwrap_or_else(|| Path::new(".")).join(default_name),
+        };
+        if output.exists() && !self.force {
+            return Err(ToolError::ConfigError(format!(
+                "{} already exists (use --force to overwrite)", output.display()
+            )));
+        }
+        std::fs::write(&output, &exported.content)?;
+        println!("{} {}", "✓".green(), format!("Exported {} → {}", config_path.display(), output.display()).bold());
+
+        print_unmapped(&exported.unmapped);
+        Ok(())
+    }
+}
+
+/// Lists the settings that were left out.
+pub fn print_unmapped(unmapped: &[String]) {
+    if !unmapped.is_empty() {
+        println!();
+        println!("{}", "Not exported:".yellow().bold());
+        for item in unmapped {
+            println!("  {} {}", "-".yellow(), item);
+        }
+    }
+}
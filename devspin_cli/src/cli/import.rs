@@ -0,0 +1,143 @@
+use std::path::{Path, PathBuf};
+
+use clap::{Args, Subcommand};
+use colored::*;
+use crate::configs::compose::from_compose;
+use crate::configs::format::{read_value, ConfigFormat};
+use crate::configs::import::Imported;
+use crate::configs::procfile::from_procfile;
+use crate::error::{Result, ToolError};
+
+#[derive(Debug, Args, Clone)]
+pub struct ImportArgs {
+    #[command(subcommand)]
+    pub source: ImportSource,
+}
+
+#[derive(Debug, Subcommand, Clone)]
+pub enum ImportSource {
+    /// Convert a docker-compose file into a devspin config
+    Compose(ComposeImportArgs),
+    /// Convert a Procfile into a devspin config
+    Procfile(ProcfileImportArgs),
+}
+
+#[derive(Debug, Args, Clone)]
+pub struct ComposeImportArgs {
+    /// Compose file to convert
+    #[arg(default_value = "docker-compose.yml")]
+    pub file: String,
+
+    #[command(flatten)]
+    pub output: ImportOutput,
+}
+
+#[derive(Debug, Args, Clone)]
+pub struct ProcfileImportArgs {
+    /// Procfile to convert (defaults to Procfile.dev if present, then Procfile)
+    pub file: Option<String>,
+
+    #[command(flatten)]
+    pub output: ImportOutput,
+}
+
+/// Where an imported config goes.
+#[derive(Debug, Args, Clone)]
+pub struct ImportOutput {
+    /// Project name (defaults to the one in the file, then the directory name)
+    #[arg(long)]
+    pub name: Option<String>,
+
+    /// Where to write the config (defaults to devspin.yaml next to the source file)
+    #[arg(long)]
+    pub output: Option<String>,
+
+    /// Overwrite the output file if it already exists
+    #[arg(long)]
+    pub force: bool,
+}
+
+impl ImportArgs {
+    pub async fn execute(&self) -> Result<()> {
+        match &self.source {
+            ImportSource::Compose(args) => args.execute(),
+            ImportSource::Procfile(args) => args.execute(),
+        }
+    }
+}
+
+impl ComposeImportArgs {
+    fn execute(&self) -> Result<()> {
+        let source = Path::new(&self.file);
+        let compose = read_value(source)?;
+        let name = self.output.project_name(source);
+        let mut imported = from_compose(&compose, &name)?;
+        if let Some(name) = &self.output.name {
+            imported.config["name"] = name.as_str().into();
+        }
+        self.output.write(source, &imported)
+    }
+}
+
+impl ProcfileImportArgs {
+    fn execute(&self) -> Result<()> {
+        let source = match &self.file {
+            Some(file) => PathBuf::from(file),
+            None => ["Procfile.dev", "Procfile"]
+                .iter()
+                .map(PathBuf::from)
+                .find(|path| path.exists())
+                .ok_or_else(|| ToolError::ConfigError("No Procfile.dev or Procfile in the current directory".to_string()))?,
+        };
+        let text = std::fs::read_to_string(&source)
+            .map_err(|e| ToolError::ConfigError(format!("Cannot read {}: {}", source.display(), e)))?;
+
+        // Procfile runners load .env from beside the Procfile
+        let env_file = source.parent().unwrap_or_else(|| Path::new(".")).join(".env");
+        let imported = from_procfile(&text, &self.output.project_name(&source), env_file.exists().then_some(".env"))?;
+        self.output.write(&source, &imported)
+    }
+}
+
+impl ImportOutput {
+    /// The `--name`, or the name of the directory holding `source`.
+    fn project_name(&self, source: &Path) -> String {
+        if let Some(name) = &self.name {
+            return name.clone();
+        }
+        std::fs::canonicalize(source)
+            .ok()
+            .and_then(|path| Some(path.parent()?.file_name()?.to_string_lossy().to_string()))
+            .unwrap_or_else(|| "app".to_string())
+    }
+
+    /// Writes the config and reports what could not be converted.
+    fn write(&self, source: &Path, imported: &Imported) -> Result<()> {
+        let output = match &self.output {
+            Some(output) => PathBuf::from(output),
+            None => source.parent().unwrap_or_else(|| Path::new(".")).join("devspin.yaml"),
+        };
+        if output.exists() && !self.force {
+            return Err(ToolError::ConfigError(format!(
+                "{} already exists (use --force to overwrite)", output.display()
+            )));
+        }
+
+        let format = ConfigFormat::from_path(&output)?;
+        std::fs::write(&output, format.serialize(&imported.config)?)?;
+
+        let services = imported.config["services"].as_sequence().map_or(0, Vec::len);
+        println!("{} {}", "✓".green(), format!(
+            "Imported {} service(s) from {} → {}", services, source.display(), output.display()
+        ).bold());
+
+        if !imported.unmapped.is_empty() {
+            println!();
+            println!("{}", "Not converted:".yellow().bold());
+            for item in &imported.unmapped {
+                println!("  {} {}", "-".yellow(), item);
+            }
+        }
+        Ok(())
+    }
+}
@@ -2,6 +2,10 @@ use clap::Args;
 use colored::*;
 use std::io::{self, Write};
 use crate::error::Result;
+use crate::configs::loader::LOCAL_OVERRIDE_PATTERN;
+use crate::configs::registry::ProjectRegistry;
+use crate::configs::compose::to_compose;
+use crate::configs::yaml_parser::ProjectConfig;
 use std::process::Command;
 use std::path::Path;
 
@@ -21,6 +25,14 @@ struct ServiceConfig {
     setup_command: String,
     health_check: HealthCheck,
     dependencies: Vec<String>,
+    /// Run as a container instead of running `command`
+    container: Option<ContainerSpec>,
+}
+
+#[derive(Debug, Clone)]
+struct ContainerSpec {
+    image: String,
+    environment: Vec<(String, String)>,
 }
 
 #[derive(Debug, Clone)]
@@ -151,6 +163,11 @@ impl InitArgs {
         if with_docker {
             self.generate_docker_files(&project_name, &template).await?;
         }
+
+        let config_path = format!("{}/devspin.yaml", project_name);
+        if let Err(e) = ProjectRegistry::ensure_registered(&project_name, Path::new(&config_path)) {
+            eprintln!("{} {}", "[WARN]".bright_yellow(), format_args!("Could not register project: {}", e));
+        }
         
         println!("\n{}", "=".repeat(50).bright_green());
         println!("{} {}", "[SUCCESS]".bright_green().bold(), format!("Project '{}' created successfully!", project_name).bold());
@@ -570,7 +587,7 @@ impl InitArgs {
             }
         }
         
-        yaml_content.push_str("\ncommands:\n  start:\n    dev: \"echo 'Starting development environment'\"\n    build: \"echo 'Building project'\"\n    test: \"echo 'Running tests'\"\n\n");
+        yaml_content.push_str("\ncommands:\n  dev: \"echo 'Starting development environment'\"\n  build: \"echo 'Building project'\"\n  test:\n    command: \"echo 'Running tests'\"\n    depends_on: [build]\n\n");
         
         yaml_content.push_str("services:\n");
         
@@ -595,17 +612,51 @@ impl InitArgs {
         
         std::fs::write(format!("{}/devspin.yaml", project_name), yaml_content)?;
         println!("   {} {}", "[OK]".bright_green(), format_args!("devspin.yaml created successfully"));
+
+        self.ignore_local_override(project_name)?;
+        Ok(())
+    }
+
+    fn ignore_local_override(&self, project_name: &str) -> Result<()> {
+        let gitignore_path = format!("{}/.gitignore", project_name);
+        let existing = std::fs::read_to_string(&gitignore_path).unwrap_or_default();
+
+        if existing.lines().any(|line| line.trim() == LOCAL_OVERRIDE_PATTERN) {
+            return Ok(());
+        }
+
+        let mut content = existing;
+        if !content.is_empty() && !content.ends_with('\n') {
+            content.push('\n');
+        }
+        content.push_str(&format!("# Personal devspin overrides\n{}\n", LOCAL_OVERRIDE_PATTERN));
+
+        std::fs::write(&gitignore_path, content)?;
+        println!("   {} {}", "[FILE]".bright_green(), format_args!("{} added to .gitignore", LOCAL_OVERRIDE_PATTERN));
         Ok(())
     }
 
     fn service_config_to_yaml(&self, config: &ServiceConfig) -> String {
+        let run = match &config.container {
+            Some(container) => {
+                let mut run = format!("    kind: container\n    image: \"{}\"\n", container.image);
+                if !container.environment.is_empty() {
+                    run.push_str("    environment:\n");
+                    for (key, value) in &container.environment {
+                        run.push_str(&format!("      {}: \"{}\"\n", key, value));
+                    }
+                }
+                run
+            }
+            None => format!("    command: \"{}\"\n    setup: \"{}\"\n", config.command, config.setup_command),
+        };
         format!(
-            "  - name: \"{}\"\n    service_type: \"{}\"\n    command: \"{}\"\n    working_dir: \"{}\"\n    setup_command: \"{}\"\n    health_check:\n      type_entry: \"{}\"\n      port: {}\n      http_target: \"{}\"\n    dependencies: [{}]\n",
+            "  - name: \"{}\"\n    service_type: \"{}\"\n{}    working_dir: \"{}\"\n    port: {}\n    health_check:\n      type_entry: \"{}\"\n      port: {}\n      http_target: \"{}\"\n    dependencies: [{}]\n",
             config.name,
             config.service_type,
-            config.command,
+            run,
             config.working_dir,
-            config.setup_command,
+            config.health_check.port,
             config.health_check.type_entry,
             config.health_check.port,
             config.health_check.http_target,
@@ -633,11 +684,20 @@ impl InitArgs {
         )?;
         println!("   {} {}", "[FILE]".bright_green(), format_args!("Dockerfile.api created"));
         
-        std::fs::write(
-            format!("{}/docker-compose.yml", project_name),
-            DOCKER_COMPOSE
-        )?;
-        println!("   {} {}", "[FILE]".bright_green(), format_args!("docker-compose.yml created"));
+        // Generated from devspin.yaml so the two describe the same services
+        let compose = ProjectConfig::load(&format!("{}/devspin.yaml", project_name), None).and_then(|project| to_compose(&project));
+        match compose {
+            Ok(compose) => {
+                std::fs::write(format!("{}/docker-compose.yml", project_name), compose.content)?;
+                println!("   {} {}", "[FILE]".bright_green(), format_args!("docker-compose.yml created"));
+                for item in &compose.unmapped {
+                    println!("   {} {}", "[NOTE]".bright_yellow(), item);
+                }
+            }
+            Err(e) => {
+                println!("   {} {}", "[WARN]".bright_yellow(), format_args!("Could not generate docker-compose.yml: {}", e));
+            }
+        }
         
         std::fs::write(
             format!("{}/.dockerignore", project_name),
@@ -763,6 +823,7 @@ impl InitArgs {
                     http_target: "http://localhost:3000".to_string(),
                 },
                 dependencies: vec![],
+                container: None,
             }],
         }
     }
@@ -834,6 +895,7 @@ impl InitArgs {
                     http_target: "http://localhost:5173".to_string(),
                 },
                 dependencies: vec![],
+                container: None,
             }],
         }
     }
@@ -905,6 +967,7 @@ impl InitArgs {
                     http_target: "http://localhost:5173".to_string(),
                 },
                 dependencies: vec![],
+                container: None,
             }],
         }
     }
@@ -972,6 +1035,7 @@ impl InitArgs {
                     http_target: "http://localhost:5173".to_string(),
                 },
                 dependencies: vec![],
+                container: None,
             }],
         }
     }
@@ -1011,6 +1075,7 @@ impl InitArgs {
                     http_target: "http://localhost:3001/health".to_string(),
                 },
                 dependencies: vec![],
+                container: None,
             }],
         }
     }
@@ -1054,6 +1119,7 @@ impl InitArgs {
                     http_target: "http://localhost:8000/health".to_string(),
                 },
                 dependencies: vec![],
+                container: None,
             }],
         }
     }
@@ -1093,6 +1159,7 @@ impl InitArgs {
                     http_target: "http://localhost:8080/health".to_string(),
                 },
                 dependencies: vec![],
+                container: None,
             }],
         }
     }
@@ -1132,6 +1199,7 @@ impl InitArgs {
                     http_target: "http://localhost:9090/health".to_string(),
                 },
                 dependencies: vec![],
+                container: None,
             }],
         }
     }
@@ -1164,6 +1232,7 @@ impl InitArgs {
                         http_target: "http://localhost:5173".to_string(),
                     },
                     dependencies: vec![],
+                    container: None,
                 },
                 ServiceConfig {
                     name: "api".to_string(),
@@ -1177,11 +1246,12 @@ impl InitArgs {
                         http_target: "http://localhost:3001/health".to_string(),
                     },
                     dependencies: vec![],
+                    container: None,
                 },
                 ServiceConfig {
                     name: "database".to_string(),
                     service_type: "database".to_string(),
-                    command: "docker run -p 5432:5432 -e POSTGRES_PASSWORD=devspin postgres:15".to_string(),
+                    command: "".to_string(),
                     working_dir: "".to_string(),
                     setup_command: "cd devspin-setup && cargo run --release".to_string(),
                     health_check: HealthCheck {
@@ -1190,6 +1260,13 @@ impl InitArgs {
                         http_target: "".to_string(),
                     },
                     dependencies: vec![],
+                    container: Some(ContainerSpec {
+                        image: "postgres:15".to_string(),
+                        environment: vec![
+                            ("POSTGRES_PASSWORD".to_string(), "devspin".to_string()),
+                            ("POSTGRES_DB".to_string(), "devspin".to_string()),
+                        ],
+                    }),
                 },
             ],
         }
@@ -2537,41 +2614,6 @@ COPY . .
 EXPOSE 3001
 CMD ["npm", "start"]"#;
 
-const DOCKER_COMPOSE: &str = r#"version: '3.8'
-services:
-  frontend:
-    build:
-      context: .
-      dockerfile: docker/Dockerfile.frontend
-    ports:
-      - "3000:3000"
-    volumes:
-      - ./frontend:/app
-      - /app/node_modules
-
-  api:
-    build:
-      context: .
-      dockerfile: docker/Dockerfile.api
-    ports:
-      - "3001:3001"
-    volumes:
-      - ./api:/app
-      - /app/node_modules
-
-  database:
-    image: postgres:15
-    environment:
-      POSTGRES_PASSWORD: devspin
-      POSTGRES_DB: devspin
-    ports:
-      - "5432:5432"
-    volumes:
-      - postgres_data:/var/lib/postgresql/data
-
-volumes:
-  postgres_data:"#;
-
 const DOCKER_IGNORE: &str = r#"node_modules
 npm-debug.log
 .git
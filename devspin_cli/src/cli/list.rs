@@ -0,0 +1,154 @@
+use clap::Args;
+use colored::*;
+use serde::Serialize;
+use crate::configs::registry::ProjectRegistry;
+use crate::configs::yaml_parser::ProjectConfig;
+use crate::error::{Result, ToolError};
+use crate::process::runs::RunRecord;
+
+#[derive(Debug, Args, Clone)]
+pub struct ListArgs {
+    /// Print the projects as JSON
+    #[arg(long)]
+    pub json: bool,
+
+    /// Only show projects that are currently running
+    #[arg(long)]
+    pub running: bool,
+}
+
+#[derive(Debug, Serialize)]
+struct ProjectSummary {
+    name: String,
+    config_path: String,
+    /// Services declared in the config, if it could be loaded
+    services: Option<usize>,
+    running: bool,
+    running_services: usize,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    error: Option<String>,
+}
+
+impl ListArgs {
+    pub async fn execute(&self) -> Result<()> {
+        let projects = self.summaries(&ProjectRegistry::load()?);
+
+        if self.json {
+            let json = serde_json::to_string_pretty(&projects)
+                .map_err(|e| ToolError::GenericError(e.to_string()))?;
+            println!("{}", json);
+            return Ok(());
+        }
+
+        println!("{}", "KNOWN PROJECTS".bright_cyan().bold());
+        println!("{}", "=".repeat(80).cyan());
+
+        if projects.is_empty() && self.running {
+            println!("{}", "No running projects".yellow());
+            return Ok(());
+        }
+
+        if projects.is_empty() {
+            println!("{}", "No projects registered yet".yellow());
+            println!("{}", "Register one with: devspin register <path>".dimmed());
+            return Ok(());
+        }
+
+        for project in &projects {
+            self.print_project(project);
+        }
+
+        println!("{}", "-".repeat(40).dimmed());
+        let running = projects.iter().filter(|p| p.running).count();
+        println!("  {}: {}  {}: {}", "Projects".dimmed(), projects.len(), "Running".green(), running);
+        Ok(())
+    }
+
+    fn summaries(&self, registry: &ProjectRegistry) -> Vec<ProjectSummary> {
+        registry
+            .projects
+            .iter()
+            .map(|(name, project)| self.summarize(name, &project.config_path.to_string_lossy()))
+            .filter(|summary| !self.running || summary.running)
+            .collect()
+    }
+
+    fn summarize(&self, name: &str, config_path: &str) -> ProjectSummary {
+        let (services, error) = match ProjectConfig::from_file(config_path) {
+            Ok(config) => (Some(config.services.map(|s| s.len()).unwrap_or(0)), None),
+            Err(e) => (None, Some(e.to_string())),
+        };
+
+        let running_services = RunRecord::load(name)
+            .ok()
+            .flatten()
+            .map(|record| record.alive_services().len())
+            .unwrap_or(0);
+
+        ProjectSummary {
+            name: name.to_string(),
+            config_path: config_path.to_string(),
+            services,
+            running: running_services > 0,
+            running_services,
+            error,
+        }
+    }
+
+    fn print_project(&self, project: &ProjectSummary) {
+        let (indicator, state) = if project.running {
+            ("●".green(), format!("running ({} processes)", project.running_services).green())
+        } else {
+            ("○".white().dimmed(), "stopped".white().dimmed())
+        };
+
+        let services = match project.services {
+            Some(count) => format!("{} services", count),
+            None => "? services".to_string(),
+        };
+
+        println!("{} {:<24} {:<28} {}", indicator, project.name.bold(), state, services.cyan());
+        println!("    {}", project.config_path.dimmed());
+
+        if let Some(error) = &project.error {
+            println!("    {} {}", "Error:".red(), error.red());
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::configs::registry::RegisteredProject;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_list_json() {
+        let temp_dir = TempDir::new().unwrap();
+        let config = temp_dir.path().join("devspin.yaml");
+        std::fs::write(&config, "name: list-shop\nservices:\n  - { name: web, command: \"npm start\", dependencies: [] }\n").unwrap();
+
+        let mut registry = ProjectRegistry::default();
+        registry.projects.insert("list-shop".to_string(), RegisteredProject { config_path: config.clone() });
+        registry.projects.insert("list-gone".to_string(), RegisteredProject { config_path: temp_dir.path().join("gone.yaml") });
+        RunRecord::record_service("list-shop", Some(&config), "web", 0, std::process::id(), "npm start").unwrap();
+
+        let args = ListArgs { json: true, running: false };
+        let json = serde_json::to_value(args.summaries(&registry)).unwrap();
+        RunRecord::forget_pid("list-shop", std::process::id()).unwrap();
+
+        assert_eq!(json[0]["name"], "list-gone");
+        assert_eq!(json[0]["services"], serde_json::Value::Null);
+        assert_eq!(json[0]["running"], false);
+        assert!(json[0]["error"].is_string());
+        assert_eq!(json[1]["name"], "list-shop");
+        assert_eq!(json[1]["config_path"], config.to_string_lossy().as_ref());
+        assert_eq!(json[1]["services"], 1);
+        assert_eq!(json[1]["running"], true);
+        assert_eq!(json[1]["running_services"], 1);
+        assert!(json[1].get("error").is_none());
+
+        let running = ListArgs { json: true, running: true };
+        assert!(running.summaries(&registry).is_empty());
+    }
+}
@@ -16,17 +16,29 @@ pub enum Commands {
     /// Stop a running project
     Stop(stop::StopArgs),
     /// List all projects
-    // List(list::ListArgs),
+    List(list::ListArgs),
+    /// Register a project so it can be started by name
+    Register(register::RegisterArgs),
     /// Show project status
     Status(status::StatusArgs),
     /// Initialize a new project
     Init(init::InitArgs),
+    /// Run a task from the project's commands
+    Run(run::RunArgs),
+    /// Run service setup steps (dependency installs and the like)
+    Setup(setup::SetupArgs),
+    /// Change how many replicas of a running service are up
+    Scale(scale::ScaleArgs),
     // /// Show project logs
     // Logs(logs::LogsArgs),
     // /// Restart a project
     // Restart(restart::RestartArgs),
-    // /// Manage project configuration
-    // Config(config::ConfigArgs),
+    /// Manage project configuration
+    Config(config::ConfigArgs),
+    /// Create a devspin config from another tool's
+    Import(import::ImportArgs),
+    /// Write the project's services out for another tool
+    Export(export::ExportArgs),
     /// Show welcome message
     Welcome,
 }
@@ -36,8 +48,16 @@ impl Cli {
         match self.command {
             Commands::Start(args) => args.execute().await,
             Commands::Stop(args) => args.execute().await,
+            Commands::List(args) => args.execute().await,
+            Commands::Register(args) => args.execute().await,
             Commands::Status(args) => args.execute().await,
             Commands::Init(args) => args.execute().await,
+            Commands::Run(args) => args.execute().await,
+            Commands::Setup(args) => args.execute().await,
+            Commands::Scale(args) => args.execute().await,
+            Commands::Config(args) => args.execute().await,
+            Commands::Import(args) => args.execute().await,
+            Commands::Export(args) => args.execute().await,
             Commands::Welcome => {
                 welcome_message::welcome_message();
                 Ok(())
@@ -50,9 +70,15 @@ impl Cli {
 pub mod start;
 pub mod stop; 
 pub mod list;
+pub mod register;
 pub mod status;
 pub mod init;
+pub mod run;
+pub mod setup;
+pub mod scale;
 pub mod welcome_message;
 // pub mod logs;
 // pub mod restart;
-// pub mod config;
\ No newline at end of file
+pub mod config;
+pub mod import;
+pub mod export;
\ No newline at end of file
@@ -0,0 +1,49 @@
+use std::path::Path;
+
+use clap::Args;
+use colored::*;
+use crate::configs::discovery::locate_project;
+use crate::configs::registry::ProjectRegistry;
+use crate::configs::yaml_parser::ProjectConfig;
+use crate::error::{Result, ToolError};
+
+#[derive(Debug, Args, Clone)]
+pub struct RegisterArgs {
+    /// Project directory or config file
+    pub path: String,
+
+    /// Replace an existing registration with the same name
+    #[arg(long)]
+    pub force: bool,
+}
+
+impl RegisterArgs {
+    pub async fn execute(&self) -> Result<()> {
+        let config_path = if Path::new(&self.path).is_file() {
+            locate_project(None, Some(&self.path))?
+        } else {
+            locate_project(Some(&self.path), None)?
+        };
+
+        let project = ProjectConfig::from_file(&config_path.to_string_lossy())?;
+        let mut registry = ProjectRegistry::load()?;
+
+        if let Some(existing) = registry.find(&project.name) {
+            let same_path = config_path.canonicalize().ok().as_ref() == Some(&existing.config_path);
+            if !same_path && !self.force {
+                return Err(ToolError::ConfigError(format!(
+                    "A project named '{}' is already registered at {} (use --force to replace it)",
+                    project.name,
+                    existing.config_path.display()
+                )));
+            }
+        }
+
+        registry.register(&project.name, &config_path)?;
+        registry.save()?;
+
+        println!("{} {}", "✓".green(), format!("Registered project '{}'", project.name).bold());
+        println!("{}", format!("Config: {}", config_path.display()).dimmed());
+        Ok(())
+    }
+}
@@ -0,0 +1,107 @@
+use clap::Args;
+use colored::*;
+use crate::configs::discovery::locate_project;
+use crate::configs::env::{parse_key_val, resolve_project_env};
+use crate::configs::tasks::task_order;
+use crate::configs::yaml_parser::ProjectConfig;
+use crate::error::{Result, ToolError};
+use crate::process::ports::assign_ports;
+use crate::process::runner::run_tasks;
+
+#[derive(Debug, Args, Clone)]
+pub struct RunArgs {
+    /// Task to run, together with the tasks it depends on
+    #[arg(required_unless_present = "list")]
+    pub task: Option<String>,
+
+    /// List the project's tasks
+    #[arg(long)]
+    pub list: bool,
+
+    /// Project name or directory (defaults to the nearest config above the current directory)
+    #[arg(short = 'p', long)]
+    pub project: Option<String>,
+
+    /// Path to the config file, bypassing project lookup
+    #[arg(short = 'f', long)]
+    pub file: Option<String>,
+
+    /// Profile to merge on top of the base config
+    #[arg(long)]
+    pub profile: Option<String>,
+
+    /// Extra env file, applied with the project's own env files
+    #[arg(long)]
+    pub env: Option<String>,
+
+    /// Override an environment variable for every task (highest precedence)
+    #[arg(long = "set", value_name = "KEY=VAL", value_parser = parse_key_val)]
+    pub set: Vec<(String, String)>,
+
+    /// Show the tasks that would run without running them
+    #[arg(long)]
+    pub dry_run: bool,
+
+    /// Rerun tasks even when their inputs and outputs are unchanged
+    #[arg(long)]
+    pub force: bool,
+}
+
+impl RunArgs {
+    pub async fn execute(&self) -> Result<()> {
+        if self.project.is_some() && self.file.is_some() {
+            return Err(ToolError::ConfigError("Cannot use both a project name and --file".to_string()));
+        }
+
+        let config_path = locate_project(self.project.as_deref(), self.file.as_deref())?;
+        let project = ProjectConfig::load(&config_path.to_string_lossy(), self.profile.as_deref())?;
+
+        let Some(target) = self.task.as_deref().filter(|_| !self.list) else {
+            self.list_tasks(&project);
+            return Ok(());
+        };
+
+        let order = task_order(&project.commands, target)?;
+
+        if self.dry_run {
+            println!("{}", "DRY RUN".bold().yellow());
+            for name in &order {
+                let task = &project.commands[*name];
+                println!("  {} {}: {}", "RUN:".green(), name.bold(), task.command.as_deref().unwrap_or("(no command)").dimmed());
+            }
+            return Ok(());
+        }
+
+        let env_files: Vec<String> = self.env.iter().cloned().collect();
+        let mut project_env = resolve_project_env(&project, &env_files)?;
+        // Tasks such as e2e tests reach services through their `<SERVICE>_PORT`
+        assign_ports(&project, &mut project_env)?;
+
+        run_tasks(&project, &order, &project_env, &self.set, self.force).await?;
+
+        println!("{} {}", "✓".green(), format!("Task '{}' completed", target).bold());
+        Ok(())
+    }
+
+    fn list_tasks(&self, project: &ProjectConfig) {
+        if project.commands.is_empty() {
+            println!("{}", format!("Project '{}' has no tasks", project.name).yellow());
+            return;
+        }
+
+        println!("{}", format!("Tasks in {}:", project.name).cyan().bold());
+        for (name, task) in &project.commands {
+            print!("  {}", name.bold());
+            if let Some(description) = &task.description {
+                print!("  {}", description);
+            }
+            println!();
+            if let Some(command) = &task.command {
+                println!("    {}", command.dimmed());
+            }
+            if !task.depends_on.is_empty() {
+                println!("    {} {}", "depends on:".dimmed(), task.depends_on.join(", "));
+            }
+        }
+    }
+}
@@ -0,0 +1,191 @@
+use std::collections::BTreeSet;
+use std::time::Duration;
+
+use clap::Args;
+use colored::*;
+use crate::configs::discovery::locate_project;
+use crate::configs::env::{resolve_project_env, resolve_service_env, ResolvedEnv};
+use crate::configs::yaml_parser::{ProjectConfig, Service, ServiceKind};
+use crate::error::{Result, ToolError};
+use crate::process::conflicts::find_conflicts;
+use crate::process::ports::{assign_ports, env_name, inject_service_ports};
+use crate::process::replicas::{self, log_path, parse_scale, replica_env, replica_label, terminate};
+use crate::process::runs::RunRecord;
+
+#[derive(Debug, Args, Clone)]
+pub struct ScaleArgs {
+    /// Project name or directory
+    pub project: String,
+
+    /// Desired replica counts, e.g. worker=4
+    #[arg(required = true, value_name = "SERVICE=N", value_parser = parse_scale)]
+    pub services: Vec<(String, u32)>,
+
+    /// Profile to load (defaults to the one the project was started with)
+    #[arg(long)]
+    pub profile: Option<String>,
+
+    /// Seconds a removed replica gets to exit on SIGTERM before it is killed
+    #[arg(long, default_value = "10")]
+    pub timeout: u64,
+}
+
+impl ScaleArgs {
+    pub async fn execute(&self) -> Result<()> {
+        let config_path = locate_project(Some(&self.project), None)?;
+        let mut project = ProjectConfig::load(&config_path.to_string_lossy(), self.profile.as_deref())?;
+
+        let record = RunRecord::load(&project.name)?
+            .filter(|record| record.is_running())
+            .ok_or_else(|| ToolError::ProcessError(format!(
+                "Project '{}' is not running; start it first", project.name
+            )))?;
+
+        // New replicas get the same config and environment as their siblings
+        let inputs = &record.inputs;
+        if self.profile.is_none() && inputs.profile.is_some() {
+            project = ProjectConfig::load(&config_path.to_string_lossy(), inputs.profile.as_deref())?;
+        }
+        let mut project_env = resolve_project_env(&project, &inputs.env_files())?;
+        assign_ports(&runtime_replicas(&project, &record, &self.services), &mut project_env)?;
+
+        for (name, count) in &self.services {
+            let service = project
+                .services
+                .iter()
+                .flatten()
+                .find(|service| &service.name == name)
+                .ok_or_else(|| ToolError::ConfigError(format!("Unknown service '{}'", name)))?;
+            if service.kind != ServiceKind::Process {
+                return Err(ToolError::ConfigError(format!(
+                    "Service '{}' is a {} service; only process services can be scaled", name, service.kind.name()
+                )));
+            }
+
+            let running: BTreeSet<(u32, u32)> = record
+                .alive_services()
+                .into_iter()
+                .filter(|s| &s.service_name == name)
+                .map(|s| (s.replica, s.pid))
+                .collect();
+
+            if running.len() < *count as usize {
+                let indices: BTreeSet<u32> = running.iter().map(|(replica, _)| *replica).collect();
+                let missing: Vec<u32> = (0..).filter(|i| !indices.contains(i)).take(*count as usize - running.len()).collect();
+                self.add_replicas(&project, service, &project_env, &inputs.overrides, &missing, *count)?;
+            } else {
+                // Highest indices go first so the remaining ones stay contiguous
+                let replicas = running.len() as u32;
+                for (replica, pid) in running.iter().rev().take(running.len() - *count as usize) {
+                    terminate(*pid, Duration::from_secs(self.timeout)).await;
+                    RunRecord::forget_pid(&project.name, *pid)?;
+                    println!("{} {}", "✓".green(), format!("Stopped {} (PID: {})", replica_label(name, *replica, replicas), pid).dimmed());
+                }
+            }
+
+            println!("{} {}", "✓".green(), format!("{} scaled to {} replica(s)", name, count).bold());
+        }
+        Ok(())
+    }
+
+    /// Spawns the given replicas after checking their ports are free. Their
+    /// output always goes to log files since this command doesn't stay attached.
+    fn add_replicas(
+        &self,
+        project: &ProjectConfig,
+        service: &Service,
+        project_env: &ResolvedEnv,
+        overrides: &[(String, String)],
+        indices: &[u32],
+        count: u32,
+    ) -> Result<()> {
+        let mut env = resolve_service_env(project, service, project_env, overrides)?;
+        inject_service_ports(service, &mut env);
+        let envs: Vec<(u32, ResolvedEnv)> = indices.iter().map(|i| (*i, replica_env(service, &env, *i))).collect();
+
+        let mut needed: Vec<(u16, String)> = Vec::new();
+        for (replica, env) in &envs {
+            let mut vars: Vec<String> = service.ports.keys().map(|name| format!("{}_PORT", env_name(name))).collect();
+            vars.push("PORT".to_string());
+            needed.extend(
+                vars.iter()
+                    .filter_map(|var| env.vars.get(var)?.parse().ok())
+                    .map(|port| (port, replica_label(&service.name, *replica, count))),
+            );
+        }
+        if let Some(conflict) = find_conflicts(&needed).first() {
+            return Err(ToolError::ProcessError(format!(
+                "Port {} needed by {} is already in use by {}", conflict.port, conflict.needed_by, conflict.owner
+            )));
+        }
+
+        let working_dir = project.service_dir(service).to_string_lossy().to_string();
+        let command = env.redactor().redact(&service.command);
+        for (replica, env) in &envs {
+            let log = log_path(&project.name, &service.name, *replica);
+            let child = replicas::spawn(&project.name, service, *replica, env, &working_dir, Some(&log))?;
+            RunRecord::record_service(&project.name, project.config_path.as_deref(), &service.name, *replica, child.id(), &command)?;
+            println!("{} {} {} {}",
+                "✓".green(),
+                format!("Started {}", replica_label(&service.name, *replica, count)).bold(),
+                format!("(PID: {})", child.id()).dimmed(),
+                format!("logs: {}", log.display()).dimmed()
+            );
+        }
+        Ok(())
+    }
+}
+
+/// The project with each service's replica count as it will be after
+/// scaling, so port blocks are reserved for every replica actually running
+/// rather than the count in the config.
+fn runtime_replicas(project: &ProjectConfig, record: &RunRecord, requested: &[(String, u32)]) -> ProjectConfig {
+    let mut project = project.clone();
+    for service in project.services.iter_mut().flatten() {
+        let running = record
+            .alive_services()
+            .iter()
+            .filter(|s| s.service_name == service.name)
+            .map(|s| s.replica + 1)
+            .max()
+            .unwrap_or(0);
+        let wanted = requested.iter().find(|(name, _)| name == &service.name).map(|(_, count)| *count).unwrap_or(0);
+        service.replicas = service.replicas.max(running).max(wanted);
+    }
+    project
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::process::runs::ServiceRecord;
+
+    #[test]
+    fn test_runtime_replicas() {
+        let project: ProjectConfig = serde_yaml::from_str(r#"
+name: shop
+services:
+  - { name: worker, command: work, dependencies: [], port: auto }
+  - { name: api, command: api, dependencies: [], port: auto }
+  - { name: web, command: web, dependencies: [], port: auto, replicas: 2 }
+"#).unwrap();
+        let replica = |service: &str, replica: u32| ServiceRecord {
+            service_name: service.to_string(),
+            replica,
+            pid: std::process::id(),
+            container_id: None,
+            command: service.to_string(),
+            started_at: std::time::SystemTime::now().duration_since(std::time::UNIX_EPOCH).unwrap().as_secs(),
+        };
+        // api was scaled to 3 earlier; web runs fewer than configured
+        let record = RunRecord {
+            project: "shop".to_string(),
+            services: vec![replica("worker", 0), replica("api", 0), replica("api", 1), replica("api", 2), replica("web", 0)],
+            ..Default::default()
+        };
+
+        let scaled = runtime_replicas(&project, &record, &[("worker".to_string(), 2)]);
+        let counts: Vec<u32> = scaled.services.unwrap().iter().map(|service| service.replicas).collect();
+        assert_eq!(counts, vec![2, 3, 2]);
+    }
+}
@@ -0,0 +1,76 @@
+use clap::Args;
+use colored::*;
+use crate::configs::discovery::locate_project;
+use crate::configs::env::{resolve_project_env, resolve_service_env};
+use crate::configs::yaml_parser::ProjectConfig;
+use crate::error::{Result, ToolError};
+use crate::process::fingerprint::FingerprintStore;
+use crate::process::setup::run_setup;
+
+#[derive(Debug, Args, Clone)]
+pub struct SetupArgs {
+    /// Service to set up (defaults to every service with a setup step)
+    pub service: Option<String>,
+
+    /// Project name or directory (defaults to the nearest config above the current directory)
+    #[arg(short = 'p', long)]
+    pub project: Option<String>,
+
+    /// Path to the config file, bypassing project lookup
+    #[arg(short = 'f', long)]
+    pub file: Option<String>,
+
+    /// Profile to merge on top of the base config
+    #[arg(long)]
+    pub profile: Option<String>,
+
+    /// Only run setup steps whose watched files changed
+    #[arg(long)]
+    pub if_needed: bool,
+}
+
+impl SetupArgs {
+    pub async fn execute(&self) -> Result<()> {
+        if self.project.is_some() && self.file.is_some() {
+            return Err(ToolError::ConfigError("Cannot use both a project name and --file".to_string()));
+        }
+
+        let config_path = locate_project(self.project.as_deref(), self.file.as_deref())?;
+        let project = ProjectConfig::load(&config_path.to_string_lossy(), self.profile.as_deref())?;
+
+        let services: Vec<_> = project
+            .services
+            .iter()
+            .flatten()
+            .filter(|service| self.service.as_ref().is_none_or(|name| &service.name == name))
+            .collect();
+
+        if let Some(name) = &self.service {
+            match services.first() {
+                None => return Err(ToolError::ConfigError(format!("Unknown service '{}'", name))),
+                Some(service) if service.setup.is_none() => {
+                    return Err(ToolError::ConfigError(format!("Service '{}' has no setup step", name)));
+                }
+                Some(_) => {}
+            }
+        }
+
+        let project_env = resolve_project_env(&project, &[])?;
+        let mut fingerprints = FingerprintStore::load(&project.name)?;
+        let mut ran = 0;
+
+        for service in services.into_iter().filter(|service| service.setup.is_some()) {
+            let env = resolve_service_env(&project, service, &project_env, &[])?;
+            if run_setup(&project, service, &env, &mut fingerprints, !self.if_needed).await? {
+                ran += 1;
+            } else {
+                println!("{} {}", "✓".green(), format!("Setup for {} is up to date", service.name).dimmed());
+            }
+        }
+
+        if ran == 0 && self.service.is_none() && project.services.iter().flatten().all(|s| s.setup.is_none()) {
+            println!("{}", format!("Project '{}' has no setup steps", project.name).yellow());
+        }
+        Ok(())
+    }
+}
@@ -1,21 +1,52 @@
-use std::collections::HashMap;
-
 use clap::Args;
 use colored::*;
 use crate::error::{Result, ToolError};
-use crate::configs::yaml_parser::{ProjectConfig, Service};
+use crate::configs::discovery::locate_project;
+use crate::configs::env::{parse_key_val, resolve_project_env, resolve_service_env, ResolvedEnv};
+use std::collections::HashSet;
+use std::time::{Duration, Instant};
+
+use crate::configs::tasks::task_order;
+use crate::configs::yaml_parser::{ProjectConfig, Service, ServiceKind, WatchAction, WatchConfig};
 use crate::process::global::get_global_state;
 use crate::process::state::ProcessState;
+use crate::process::runs::{RunRecord, StartInputs};
+use crate::process::hooks::{run_hook, HookContext, HookStage};
+use crate::process::fingerprint::FingerprintStore;
+use crate::process::setup::{run_setup, setup_needed};
+use crate::process::runner::run_tasks;
+use crate::process::watch::{dependents_of, FileWatcher};
+use crate::process::ports::{assign_ports, env_name, inject_service_ports};
+use crate::process::conflicts::{find_conflicts, kill_owner};
+use crate::process::replicas::{self, log_path, replica_env, replica_label, tail_lines};
+use crate::process::jobs::{format_duration, run_job};
+use crate::process::health::{external_vars, probe, wait_until_healthy};
+use crate::process::containers;
+use nix::sys::signal::{kill, Signal};
+use nix::unistd::Pid;
+use crate::configs::registry::ProjectRegistry;
 use log::debug; 
 
 #[derive(Debug, Args, Clone)]
 pub struct StartArgs {
-    /// Project name
-    pub name: String,
+    /// Project name or directory (defaults to the nearest config above the current directory)
+    pub name: Option<String>,
+
+    /// Path to the config file, bypassing project lookup
+    #[arg(short = 'f', long)]
+    pub file: Option<String>,
     
-    /// Environment configuration file
+    /// Extra env file, applied with the project's own env files
     #[arg(long)]
     pub env: Option<String>,
+
+    /// Override an environment variable for every service (highest precedence)
+    #[arg(long = "set", value_name = "KEY=VAL", value_parser = parse_key_val)]
+    pub set: Vec<(String, String)>,
+
+    /// Profile to merge on top of the base config (e.g. dev, test, ci)
+    #[arg(long)]
+    pub profile: Option<String>,
     
     /// Show detailed output
     #[arg(long)]
@@ -35,38 +66,52 @@ pub struct StartArgs {
 
     /// Skip specific services
     #[arg(long, value_delimiter = ',')]
-    pub skip: Option<Vec<String>>
+    pub skip: Option<Vec<String>>,
+
+    /// Stay attached and restart services when their watched files change
+    #[arg(long)]
+    pub watch: bool,
+
+    /// Stop processes left over from a previous run of this project that hold a needed port
+    #[arg(long)]
+    pub kill_conflicting: bool,
 }
 
+/// How often watched files are scanned in `--watch` mode.
+const WATCH_POLL_INTERVAL: Duration = Duration::from_millis(250);
+
+/// Lines of a failed job's output shown when it fails the start.
+const JOB_OUTPUT_LINES: usize = 20;
+
+/// Grace period for a service to exit on SIGTERM before a restart kills it.
+const RESTART_STOP_TIMEOUT: Duration = Duration::from_secs(10);
+
 #[allow(clippy::await_holding_lock)]
 impl StartArgs {
     pub async fn execute(&self) -> Result<()> {
-        println!("{}", format!("Starting project: {}", self.name).bold());
-
         self.validate_args()?;
 
-        let default_path = format!("{}/devspin.yaml", self.name);
-        if !std::path::Path::new(&default_path).exists() {
-            return Err(ToolError::ConfigError(format!(
-                "Project '{}' not found at: {}", self.name, default_path  
-            )))
+        let config_path = locate_project(self.name.as_deref(), self.file.as_deref())?;
+        println!("{}", format!("Starting project from: {}", config_path.display()).bold());
+        let project = self.load_project(&config_path.to_string_lossy()).await?;
+
+        if !self.dry_run {
+            self.register_project(&project, &config_path);
         }
-        let project = self.load_project(&default_path).await?;
 
         if self.dry_run {
             return self.dry_run(&project);
         }
 
-        // Load environment file if specified
+        // Env files and secrets are resolved once, only when really starting
         if let Some(env) = &self.env {
             println!("{}", format!("Loading environment from: {}", env).dimmed());
-            self.load_env_file(env).await?;
-            
-            if self.verbose {
-                // FIX: Use if-let without collect() to avoid the type error
-                let env_count = std::env::vars().count();
-                println!("{} {} environment variables loaded", "✓".green(), env_count.to_string().cyan());
-            }
+        }
+        let mut project_env = resolve_project_env(&project, &self.extra_env_files())?;
+        assign_ports(&project, &mut project_env)?;
+        self.check_port_conflicts(&project, &project_env)?;
+        if self.verbose {
+            println!("{} {} environment variables loaded", "✓".green(), project_env.vars.len().to_string().cyan());
         }
 
         if self.verbose {
@@ -76,7 +121,10 @@ impl StartArgs {
 
         if self.background {
             println!("{}", "Running in background mode".bold());
-            return self.start_in_background(project).await;
+            let project_name = project.name.clone();
+            self.start_in_background(project, &project_env).await?;
+            self.record_inputs(&project_name);
+            return Ok(());
         }
 
         if let Some(only_services) = &self.only {
@@ -89,50 +137,131 @@ impl StartArgs {
 
         // For foreground mode, use global state directly
         let mut process_state: std::sync::MutexGuard<'static, ProcessState> = get_global_state();
-        self.start_services(&project, &mut process_state).await
+        self.start_services(&project, &project_env, &mut process_state).await?;
+        self.record_inputs(&project.name);
+
+        if self.watch {
+            self.watch_services(&project, &project_env, &mut process_state).await?;
+        }
+        Ok(())
     }
 
     async fn load_project(&self, path: &str) -> Result<ProjectConfig> {
         debug!("Loading project from: {}", path);
-        let project = ProjectConfig::from_file(path)?;
+        let project = ProjectConfig::load(path, self.profile.as_deref())?;
         println!("{}", format!("Loaded project: {}", project.name).bold());
+        if let Some(profile) = &project.active_profile {
+            println!("{}", format!("Using profile: {}", profile).dimmed());
+        }
         Ok(project)
     }
 
-    async fn load_env_file(&self, env_file: &str) -> Result<()> {
-        dotenvy::from_filename(env_file)
-            .map_err(|e| ToolError::ConfigError(format!("Failed to load env file {}: {}", env_file, e)))?;
-        Ok(())
+    fn register_project(&self, project: &ProjectConfig, config_path: &std::path::Path) {
+        match ProjectRegistry::ensure_registered(&project.name, config_path) {
+            Ok(true) => println!("{}", format!("Registered project '{}'", project.name).dimmed()),
+            Ok(false) => {}
+            Err(e) => eprintln!("{} {}", "WARN:".yellow(), format!("Could not register project: {}", e).yellow()),
+        }
+    }
+
+    /// Keeps `--profile`, `--env` and `--set` with the run for `stop` and `scale`.
+    fn record_inputs(&self, project_name: &str) {
+        let inputs = StartInputs {
+            profile: self.profile.clone(),
+            env_file: self.env.as_ref().map(|env| std::path::absolute(env).unwrap_or_else(|_| env.into())),
+            overrides: self.set.clone(),
+        };
+        if let Err(e) = RunRecord::record_inputs(project_name, inputs) {
+            eprintln!("{} {}", "WARN:".yellow(), format!("Could not record start options: {}", e).yellow());
+        }
+    }
+
+    fn extra_env_files(&self) -> Vec<String> {
+        self.env.iter().cloned().collect()
+    }
+
+    /// Environment for one service's child process (see `configs::env` for precedence).
+    fn service_env(&self, project: &ProjectConfig, service: &Service, project_env: &ResolvedEnv) -> Result<ResolvedEnv> {
+        let mut env = resolve_service_env(project, service, project_env, &self.set)?;
+        inject_service_ports(service, &mut env);
+        Ok(env)
+    }
+
+    async fn run_project_hook(&self, project: &ProjectConfig, stage: HookStage, project_env: &ResolvedEnv) -> Result<()> {
+        let working_dir = project.resolve_path("");
+        let mut env = project_env.clone();
+        env.apply_overrides(&self.set);
+        let ctx = HookContext {
+            project: &project.name,
+            service: None,
+            working_dir: &working_dir,
+            env: &env.vars,
+            redactor: &env.redactor(),
+            verbose: self.verbose,
+        };
+        run_hook(project.hooks.as_ref(), stage, &ctx).await
+    }
+
+    async fn run_service_hook(&self, project: &ProjectConfig, service: &Service, working_dir: &str, stage: HookStage, env: &ResolvedEnv) -> Result<()> {
+        let ctx = HookContext {
+            project: &project.name,
+            service: Some(&service.name),
+            working_dir: std::path::Path::new(working_dir),
+            env: &env.vars,
+            redactor: &env.redactor(),
+            verbose: self.verbose,
+        };
+        run_hook(service.hooks.as_ref(), stage, &ctx).await
     }
 
     fn show_verbose_configuration(&self, project: &ProjectConfig) {
         println!();
         println!("{}", "CONFIGURATION DETAILS:".cyan().bold());
-        println!("  {} {}", "Config path:".dimmed(), format_args!("./{}/devspin.yaml", self.name));
+        if let Some(config_path) = &project.config_path {
+            println!("  {} {}", "Config path:".dimmed(), config_path.display());
+        }
         println!("  {} {}", "Project:".dimmed(), project.name.cyan());
         println!("  {} {:?}", "Description:".dimmed(), project.description);
+
+        if let Some(profile) = &project.active_profile {
+            println!("  {} {}", "Profile:".dimmed(), profile.cyan());
+        }
+
+        if project.sources.len() > 1 {
+            println!("  {}", "Merged config files:".dimmed());
+            for source in &project.sources {
+                println!("    {} {}", "•".dimmed(), source.display());
+            }
+        }
         
         if let Some(env) = &self.env {
             println!("  {} {}", "Environment file:".dimmed(), env.cyan());
         }
+
+        if !project.env_file.is_empty() {
+            println!("  {} {}", "Project env files:".dimmed(), project.env_file.join(", ").cyan());
+        }
+
+        if !self.set.is_empty() {
+            println!("  {}", "Overrides (--set):".cyan().bold());
+            for (key, value) in &self.set {
+                println!("    {} {}={}", "•".dimmed(), key.blue(), value.dimmed());
+            }
+        }
         
         println!("  {} only={:?}, skip={:?}", "Service filters:".dimmed(), self.only, self.skip);
         
-        println!("  {}", "Commands:".cyan().bold());
-        println!("    {} {}", "Dev:".green(), project.commands.start.dev);
-        if let Some(test) = &project.commands.start.test {
-            println!("    {} {}", "Test:".yellow(), test);
-        }
-        println!("    {} {}", "Build:".blue(), project.commands.start.build);
-
-        if let Some(clean) = &project.commands.start.clean {
-            println!("    {} {}", "Clean:".red(), clean);
+        if !project.commands.is_empty() {
+            println!("  {}", "Tasks:".cyan().bold());
+            for (name, task) in &project.commands {
+                println!("    {} {}", format!("{}:", name).green(), task.command.as_deref().unwrap_or("-"));
+            }
         }
         
         if let Some(env_vars) = &project.environment {
             println!("  {} ({})", "Environment variables:".cyan().bold(), env_vars.len());
             for (key, value) in env_vars {
-                println!("    {} {}={}", "•".dimmed(), key.blue(), value.dimmed());
+                println!("    {} {}={}", "•".dimmed(), key.blue(), value.to_string().dimmed());
             }
         }
         
@@ -150,6 +279,7 @@ impl StartArgs {
             if let Some(post_stop) = &hooks.post_stop {
                 println!("    {} {}", "Post-stop:".red(), post_stop);
             }
+            println!("    {} {:?}, timeout {}s", "On failure:".dimmed(), hooks.on_failure, hooks.timeout);
         }
         
         if let Some(services) = &project.services {
@@ -164,16 +294,61 @@ impl StartArgs {
                 };
                 
                 println!("  {} {}:", status, service.name.bold());
-                println!("    {} {}", "Type:".dimmed(), service.service_type.cyan());
+                println!("    {} {}", "Kind:".dimmed(), service.kind.name().cyan());
+                if let Some(label) = &service.service_type {
+                    println!("    {} {}", "Type:".dimmed(), label.cyan());
+                }
+                if let Some(image) = &service.image {
+                    println!("    {} {}", "Image:".dimmed(), image.cyan());
+                }
                 println!("    {} {}", "Command:".dimmed(), service.command.dimmed());
                 
                 if let Some(dir) = &service.working_dir {
                     println!("    {} {}", "Working directory:".dimmed(), dir.blue());
                 }
+
+                if let Some(setup) = &service.setup {
+                    println!("    {} {}", "Setup:".dimmed(), setup.command.dimmed());
+                }
                 
                 if !service.dependencies.is_empty() {
                     println!("    {} {:?}", "Dependencies:".dimmed(), service.dependencies);
                 }
+
+                if service.replicas != 1 {
+                    println!("    {} {}", "Replicas:".dimmed(), service.replicas.to_string().cyan());
+                }
+
+                if let Some(limits) = &service.limits {
+                    println!("    {} {:?}", "Limits:".dimmed(), limits);
+                }
+
+                if let Some(port) = &service.port {
+                    println!("    {} {}", "Port:".dimmed(), port.to_string().cyan());
+                }
+                for (name, port) in &service.ports {
+                    println!("    {} {}", format!("Port {}:", name).dimmed(), port.to_string().cyan());
+                }
+
+                if !service.env_file.is_empty() {
+                    println!("    {} {}", "Env files:".dimmed(), service.env_file.join(", ").blue());
+                }
+
+                if let Some(env_vars) = &service.environment {
+                    println!("    {} ({})", "Environment:".dimmed(), env_vars.len());
+                    for (key, value) in env_vars {
+                        println!("      {} {}={}", "•".dimmed(), key.blue(), value.to_string().dimmed());
+                    }
+                }
+
+                if let Some(hooks) = &service.hooks {
+                    let hook_list = [("pre_start", &hooks.pre_start), ("post_start", &hooks.post_start), ("pre_stop", &hooks.pre_stop), ("post_stop", &hooks.post_stop)];
+                    for (stage, command) in hook_list {
+                        if let Some(command) = command {
+                            println!("    {} {}", format!("Hook {}:", stage).dimmed(), command);
+                        }
+                    }
+                }
                 
                 if let Some(health_check) = &service.health_check {
                     println!("    {}", "Health check:".yellow().bold());
@@ -187,7 +362,9 @@ impl StartArgs {
                 }
                 
                 if !should_start {
-                    let reason = if let Some(only_services) = &self.only {
+                    let reason = if !service.enabled {
+                        "disabled in config"
+                    } else if let Some(only_services) = &self.only {
                         if !only_services.contains(&service.name) {
                             "filtered out by --only"
                         } else {
@@ -220,6 +397,9 @@ impl StartArgs {
     pub fn dry_run(&self, project: &ProjectConfig) -> Result<()> {
         println!("{}", "DRY RUN".bold().yellow());
         println!("{}", format!("Would start project: {}", project.name).bold());
+        if let Some(profile) = &project.active_profile {
+            println!("{} {}", "Profile:".dimmed(), profile.cyan());
+        }
 
         if self.verbose {
             self.show_verbose_configuration(project);
@@ -232,11 +412,16 @@ impl StartArgs {
         }
         
         if let Some(services) = &project.services {
+            let fingerprints = FingerprintStore::load(&project.name)?;
             println!();
             println!("{}", "SERVICES:".cyan().bold());
             for service in services {
                 let should_start = self.should_start_service(service);
                 
+                if should_start && setup_needed(project, service, &fingerprints)? {
+                    println!("  {} {}: {}", "SETUP:".blue(), service.name.bold(), service.setup.as_ref().map(|s| s.command.as_str()).unwrap_or_default().dimmed());
+                }
+
                 if self.verbose {
                     let status = if should_start { 
                         format!("{}: {}", "STATUS".white(), "START".green())
@@ -245,12 +430,22 @@ impl StartArgs {
                     };
                     
                     println!("  {} {}:", status, service.name.bold());
+                    println!("    {} {}", "Kind:".dimmed(), service.kind.name().cyan());
                     println!("    {} {}", "Command:".dimmed(), service.command.dimmed());
                     
                     if !should_start {
                         println!("    {} {}", "Status:".dimmed(), "SKIPPED (filtered out)".yellow());
                     }
                     println!();
+                } else if should_start && service.kind == ServiceKind::External {
+                    println!("  {} {}: {}", "CHECK:".blue(), service.name.bold(), "(external, health check only)".dimmed());
+                } else if should_start && service.kind == ServiceKind::Container {
+                    let image = format!("{} (container)", service.image.as_deref().unwrap_or_default());
+                    println!("  {} {}: {}", "START:".green(), service.name.bold(), image.dimmed());
+                } else if should_start && service.kind == ServiceKind::Oneshot {
+                    println!("  {} {}: {}", "RUN:".blue(), service.name.bold(), service.command.dimmed());
+                } else if should_start && service.replicas != 1 {
+                    println!("  {} {} x{}: {}", "START:".green(), service.name.bold(), service.replicas, service.command.dimmed());
                 } else if should_start {
                     println!("  {} {}: {}", "START:".green(), service.name.bold(), service.command.dimmed());
                 } else {
@@ -270,6 +465,10 @@ impl StartArgs {
     }
 
     fn should_start_service(&self, service: &Service) -> bool {
+        if !service.enabled {
+            return false;
+        }
+
         if let Some(only_services) = &self.only {
             if !only_services.contains(&service.name) {
                 return false;
@@ -286,35 +485,392 @@ impl StartArgs {
 
     async fn spawn_service_command(
         &self, 
+        project_name: &str,
         service: &Service, 
-        env_vars: &HashMap<String, String>,
-        working_dir: &str
+        replica: u32,
+        env: &ResolvedEnv,
+        working_dir: &str,
+        log: Option<&std::path::Path>,
     ) -> Result<std::process::Child> {
-        let mut command = std::process::Command::new("sh");
-        command.arg("-c").arg(&service.command);
+        if self.verbose {
+            debug!("Spawning command: sh -c '{}' in directory: {}", env.redactor().redact(&service.command), working_dir);
+        }
         
         // Use the resolved working directory
-        command.current_dir(working_dir);
-        
-        for (key, value) in env_vars {
-            command.env(key, value);
+        replicas::spawn(project_name, service, replica, env, working_dir, log)
+    }
+
+    /// Fails before anything is spawned if a port a service needs is taken,
+    /// naming the process that holds it.
+    fn check_port_conflicts(&self, project: &ProjectConfig, project_env: &ResolvedEnv) -> Result<()> {
+        let mut needed: Vec<(u16, String)> = Vec::new();
+        // External services hold their own ports; that is how they are found
+        let started = project.services.iter().flatten().filter(|s| self.should_start_service(s) && s.kind != ServiceKind::External);
+        for service in started {
+            let service_env = self.service_env(project, service, project_env)?;
+            for replica in 0..service.replicas {
+                let env = replica_env(service, &service_env, replica);
+                let label = replica_label(&service.name, replica, service.replicas);
+                let mut ports: Vec<(Option<u16>, String)> = vec![(env.vars.get("PORT").and_then(|p| p.parse().ok()), label.clone())];
+                for name in service.ports.keys() {
+                    let port = env.vars.get(&format!("{}_PORT", env_name(name))).and_then(|p| p.parse().ok());
+                    ports.push((port, format!("{} ({})", label, name)));
+                }
+                if let Some(health_check) = &service.health_check {
+                    ports.push((health_check.resolve(&env.vars).port, format!("{} (health check)", label)));
+                }
+
+                for (port, label) in ports {
+                    if let Some(port) = port.filter(|port| !needed.iter().any(|(used, _)| used == port)) {
+                        needed.push((port, label));
+                    }
+                }
+            }
         }
-        
-        if self.verbose {
-            debug!("Spawning command: sh -c '{}' in directory: {}", service.command, working_dir);
+
+        let mut unresolved = 0;
+        for conflict in find_conflicts(&needed) {
+            // Only leftovers; a live run of this project is stopped with `devspin stop`
+            let stale = conflict.owner.devspin.as_ref().is_some_and(|owner| owner.project == project.name && owner.stale);
+            if self.kill_conflicting && stale {
+                kill_owner(&conflict)?;
+                println!("{} {}", "✓".green(), format!("Stopped stale {} holding port {}", conflict.owner, conflict.port).dimmed());
+                continue;
+            }
+
+            unresolved += 1;
+            eprintln!("{} {}", "ERROR:".red(), format!(
+                "Port {} needed by {} is already in use by {}", conflict.port, conflict.needed_by, conflict.owner
+            ).red());
+            match &conflict.owner.devspin {
+                Some(owner) if owner.project == project.name && owner.stale => {
+                    eprintln!("  {}", "Left over from a previous run; use --kill-conflicting to stop it".dimmed());
+                }
+                Some(owner) if owner.project == project.name => {
+                    eprintln!("  {}", format!("This project is already running; stop it first with: devspin stop {}", owner.project).dimmed());
+                }
+                Some(owner) => {
+                    eprintln!("  {}", format!("Stop it with: devspin stop {}", owner.project).dimmed());
+                }
+                None => {}
+            }
         }
-        
-        let child = command.spawn()?;
-        Ok(child)
+
+        if unresolved > 0 {
+            return Err(ToolError::ProcessError(format!("{} port conflict(s); nothing was started", unresolved)));
+        }
+        Ok(())
+    }
+
+    /// External services are never spawned; they only have to pass their health check.
+    async fn check_external_service(&self, project: &ProjectConfig, service: &Service, project_env: &ResolvedEnv) -> Result<()> {
+        let env = self.service_env(project, service, project_env)?;
+        if let Some(health_check) = &service.health_check {
+            self.wait_for_health_check(service, &health_check.resolve(&env.vars)).await?;
+        }
+        RunRecord::record_external(&project.name, project.config_path.as_deref(), &service.name)?;
+        println!("{} {}", "✓".green(), format!("External service ready: {}", service.name).bold());
+        Ok(())
+    }
+
+    /// Runs a oneshot service to completion. A non-zero exit fails the start
+    /// with the tail of the job's output, so its dependents never start.
+    async fn run_oneshot(
+        &self,
+        project: &ProjectConfig,
+        service: &Service,
+        project_env: &ResolvedEnv,
+        fingerprints: &mut FingerprintStore,
+    ) -> Result<()> {
+        let working_dir = project.service_dir(service).to_string_lossy().to_string();
+        let env = self.service_env(project, service, project_env)?;
+        run_setup(project, service, &env, fingerprints, false).await?;
+        self.run_service_hook(project, service, &working_dir, HookStage::PreStart, &env).await?;
+
+        println!("{}", format!("Running job: {}", service.name).dimmed());
+        let job = run_job(&project.name, service, &env, &working_dir).await?;
+        RunRecord::record_job(&project.name, project.config_path.as_deref(), job.clone())?;
+
+        if !job.succeeded() {
+            eprintln!("{} {}", "ERROR:".red(), format!(
+                "Job {} failed with exit code {} after {}", service.name, job.exit_code, format_duration(job.duration())
+            ).red());
+            let redactor = env.redactor();
+            for line in tail_lines(&log_path(&project.name, &service.name, 0), JOB_OUTPUT_LINES) {
+                eprintln!("  {}", redactor.redact(&line).dimmed());
+            }
+            return Err(ToolError::ProcessError(format!("Job '{}' exited with code {}", service.name, job.exit_code)));
+        }
+
+        println!("{} {} {}",
+            "✓".green(),
+            format!("Job completed: {}", service.name).bold(),
+            format!("in {}", format_duration(job.duration())).dimmed()
+        );
+        self.run_service_hook(project, service, &working_dir, HookStage::PostStart, &env).await
+    }
+
+    /// Starts every replica of a container service with `docker run`,
+    /// recording the container IDs. Attached starts pass their process state
+    /// to stream the containers' output with `docker logs --follow`.
+    async fn launch_container(
+        &self,
+        project: &ProjectConfig,
+        service: &Service,
+        project_env: &ResolvedEnv,
+        fingerprints: &mut FingerprintStore,
+        mut attached: Option<&mut ProcessState>,
+    ) -> Result<()> {
+        let working_dir = project.service_dir(service);
+        let working_dir_str = working_dir.to_string_lossy().to_string();
+        let env = self.service_env(project, service, project_env)?;
+        run_setup(project, service, &env, fingerprints, false).await?;
+        self.run_service_hook(project, service, &working_dir_str, HookStage::PreStart, &env).await?;
+
+        let image = service.image.as_deref().unwrap_or_default();
+        for replica in 0..service.replicas {
+            let replica_env = replica_env(service, &env, replica);
+            let id = containers::run(&project.name, service, replica, &replica_env, &working_dir)?;
+            RunRecord::record_container(&project.name, project.config_path.as_deref(), &service.name, replica, &id, image)?;
+
+            println!("{} {} {} {}",
+                "✓".green(),
+                format!("Started container: {}", replica_label(&service.name, replica, service.replicas)).bold(),
+                format!("({})", containers::short_id(&id)).dimmed(),
+                image.blue()
+            );
+            if let Some(process_state) = attached.as_deref_mut() {
+                process_state.add_log_follower(containers::follow_logs(&id)?);
+            }
+
+            if let Some(health_check) = &service.health_check {
+                self.wait_for_health_check(service, &health_check.resolve(&replica_env.vars)).await?;
+            }
+        }
+        self.run_service_hook(project, service, &working_dir_str, HookStage::PostStart, &env).await
     }
 
-    async fn start_services(&self, project: &ProjectConfig, process_state: &mut ProcessState) -> Result<()> {
-        let env_vars = project.environment.clone().unwrap_or_default();
+    /// Sets up, spawns and tracks every replica of one service, running its start hooks once.
+    async fn launch_service(
+        &self,
+        project: &ProjectConfig,
+        service: &Service,
+        project_env: &ResolvedEnv,
+        process_state: &mut ProcessState,
+        fingerprints: &mut FingerprintStore,
+    ) -> Result<()> {
+        match service.kind {
+            ServiceKind::External => return self.check_external_service(project, service, project_env).await,
+            ServiceKind::Oneshot => return self.run_oneshot(project, service, project_env, fingerprints).await,
+            ServiceKind::Container => return self.launch_container(project, service, project_env, fingerprints, Some(process_state)).await,
+            ServiceKind::Process => {}
+        }
+
+        // RESOLVE the working directory relative to project base
+        let working_dir = if let Some(service_dir) = &service.working_dir {
+            project.resolve_path(service_dir).to_string_lossy().to_string()
+        } else {
+            // Default to project base directory
+            project.base_path.as_ref()
+                .map(|p| p.to_string_lossy().to_string())
+                .unwrap_or_else(|| ".".to_string())
+        };
         
+        let env = self.service_env(project, service, project_env)?;
+        run_setup(project, service, &env, fingerprints, false).await?;
+        self.run_service_hook(project, service, &working_dir, HookStage::PreStart, &env).await?;
+
+        for replica in 0..service.replicas {
+            let replica_env = replica_env(service, &env, replica);
+            let log = (service.replicas > 1).then(|| log_path(&project.name, &service.name, replica));
+            let child = self.spawn_service_command(&project.name, service, replica, &replica_env, &working_dir, log.as_deref()).await?;
+            let pid = child.id();
+
+            // Recorded commands show up in status output, so keep secrets out
+            let command = env.redactor().redact(&service.command);
+            process_state.add_process(child, &service.name, replica, &project.name, &command)?;
+            RunRecord::record_service(&project.name, project.config_path.as_deref(), &service.name, replica, pid, &command)?;
+
+            println!("{} {} {} {}", 
+                "✓".green(), 
+                format!("Started service: {}", replica_label(&service.name, replica, service.replicas)).bold(),
+                format!("(PID: {})", pid).dimmed(),
+                format!("in directory: {}", working_dir).blue()
+            );
+            if let Some(log) = &log {
+                println!("  {} {}", "Logs:".dimmed(), log.display().to_string().dimmed());
+            }
+
+            if let Some(health_check) = &service.health_check {
+                self.wait_for_health_check(service, &health_check.resolve(&replica_env.vars)).await?;
+            }
+        }
+        self.run_service_hook(project, service, &working_dir, HookStage::PostStart, &env).await
+    }
+
+    /// Stops every replica of one service started by this invocation, running its stop hooks.
+    /// Containers are found through the run record, since only processes are held here.
+    async fn stop_service(
+        &self,
+        project: &ProjectConfig,
+        service: &Service,
+        project_env: &ResolvedEnv,
+        process_state: &mut ProcessState,
+    ) -> Result<()> {
+        let container_ids: Vec<String> = match service.kind {
+            ServiceKind::Container => RunRecord::load(&project.name)?
+                .map(|record| record.services.into_iter()
+                    .filter(|running| running.service_name == service.name)
+                    .filter_map(|running| running.container_id)
+                    .collect())
+                .unwrap_or_default(),
+            _ => Vec::new(),
+        };
+        if container_ids.is_empty() && !process_state.is_service_running(&project.name, &service.name) {
+            return Ok(());
+        }
+
+        let env = self.service_env(project, service, project_env)?;
+        let working_dir = project.service_dir(service).to_string_lossy().to_string();
+        self.run_service_hook(project, service, &working_dir, HookStage::PreStop, &env).await?;
+
+        for id in container_ids {
+            containers::stop(&id, RESTART_STOP_TIMEOUT.as_secs())?;
+            RunRecord::forget_container(&project.name, &id)?;
+        }
+        while let Some(mut running) = process_state.take_service(&project.name, &service.name) {
+            let pid = running.child.id();
+            let _ = kill(Pid::from_raw(pid as i32), Signal::SIGTERM);
+            let deadline = Instant::now() + RESTART_STOP_TIMEOUT;
+            while running.child.try_wait()?.is_none() {
+                if Instant::now() >= deadline {
+                    running.child.kill()?;
+                    running.child.wait()?;
+                    break;
+                }
+                tokio::time::sleep(Duration::from_millis(100)).await;
+            }
+            RunRecord::forget_pid(&project.name, pid)?;
+        }
+
+        self.run_service_hook(project, service, &working_dir, HookStage::PostStop, &env).await
+    }
+
+    /// Polls the `watch:` files of the started services until Ctrl-C and
+    /// reacts to changes once they settle for the service's debounce period.
+    async fn watch_services(
+        &self,
+        project: &ProjectConfig,
+        project_env: &ResolvedEnv,
+        process_state: &mut ProcessState,
+    ) -> Result<()> {
+        let mut watchers: Vec<(&Service, &WatchConfig, FileWatcher, Option<Instant>)> = Vec::new();
+        for service in project.services.iter().flatten().filter(|s| self.should_start_service(s)) {
+            let Some(config) = &service.watch else { continue };
+            if config.action == WatchAction::RunTask && config.task.is_none() {
+                return Err(ToolError::ConfigError(format!(
+                    "Service '{}' watches with action run-task but names no task", service.name
+                )));
+            }
+            watchers.push((service, config, FileWatcher::new(&project.service_dir(service), config)?, None));
+        }
+
+        if watchers.is_empty() {
+            println!("{}", "No started service has a watch: block, nothing to watch".yellow());
+            return Ok(());
+        }
+
+        let names: Vec<&str> = watchers.iter().map(|(service, ..)| service.name.as_str()).collect();
+        println!("{} {}", "Watching for changes:".cyan().bold(), names.join(", "));
+        println!("{}", "Press Ctrl-C to stop watching".dimmed());
+
+        let mut fingerprints = FingerprintStore::load(&project.name)?;
+        let ctrl_c = tokio::signal::ctrl_c();
+        tokio::pin!(ctrl_c);
+
+        loop {
+            tokio::select! {
+                _ = &mut ctrl_c => {
+                    println!("{}", "Stopped watching".dimmed());
+                    return Ok(());
+                }
+                _ = tokio::time::sleep(WATCH_POLL_INTERVAL) => {}
+            }
+
+            for (service, config, watcher, pending) in watchers.iter_mut() {
+                let changed = match watcher.poll() {
+                    Ok(changed) => changed,
+                    Err(e) => {
+                        eprintln!("{} {}", "WARN:".yellow(), format!("Could not scan files of {}: {}", service.name, e).yellow());
+                        continue;
+                    }
+                };
+
+                if !changed.is_empty() {
+                    if self.verbose {
+                        for path in &changed {
+                            println!("  {} {}", "changed:".dimmed(), path.display());
+                        }
+                    }
+                    *pending = Some(Instant::now());
+                } else if pending.is_some_and(|since| since.elapsed() >= Duration::from_millis(config.debounce_ms)) {
+                    *pending = None;
+                    if let Err(e) = self.on_change(project, service, config, project_env, process_state, &mut fingerprints).await {
+                        eprintln!("{} {}", "ERROR:".red(), e.to_string().red());
+                    }
+                }
+            }
+        }
+    }
+
+    async fn on_change(
+        &self,
+        project: &ProjectConfig,
+        service: &Service,
+        config: &WatchConfig,
+        project_env: &ResolvedEnv,
+        process_state: &mut ProcessState,
+        fingerprints: &mut FingerprintStore,
+    ) -> Result<()> {
+        match config.action {
+            WatchAction::RunTask => {
+                let task = config.task.as_deref().unwrap_or_default();
+                println!("{} {}", "↻".cyan(), format!("{} changed, running task {}", service.name, task).bold());
+                let order = task_order(&project.commands, task)?;
+                run_tasks(project, &order, project_env, &self.set, false).await
+            }
+            WatchAction::Restart => {
+                println!("{} {}", "↻".cyan(), format!("{} changed, restarting", service.name).bold());
+
+                let mut targets = vec![service];
+                if config.restart_dependents {
+                    let services = project.services.as_deref().unwrap_or_default();
+                    targets.extend(dependents_of(services, &service.name).into_iter().filter(|s| self.should_start_service(s)));
+                }
+
+                // Dependents go down first and come back up last
+                for target in targets.iter().rev() {
+                    self.stop_service(project, target, project_env, process_state).await?;
+                }
+                for target in targets {
+                    self.launch_service(project, target, project_env, process_state, fingerprints).await?;
+                }
+                // The record may have ended with the last stopped service
+                self.record_inputs(&project.name);
+                Ok(())
+            }
+        }
+    }
+
+    async fn start_services(&self, project: &ProjectConfig, project_env: &ResolvedEnv, process_state: &mut ProcessState) -> Result<()> {
+        self.run_project_hook(project, HookStage::PreStart, project_env).await?;
+        let mut fingerprints = FingerprintStore::load(&project.name)?;
+
         if let Some(services) = &project.services {
             println!("{}", "Starting services...".cyan());
 
             let sorted_services = self.sort_services_by_dependencies(services);
+            let mut ready = HashSet::new();
             
             if self.verbose {
                 println!("  {} services in dependency order:", "Starting".green());
@@ -336,12 +892,15 @@ impl StartArgs {
                         println!("{}", "─".repeat(50).dimmed());
                     }
                     
-                    self.wait_for_dependencies(service, &*process_state, &project.name).await?;
+                    self.check_dependencies(project, service, &ready).await?;
 
                     println!("{}", format!("Starting service: {}", service.name).bold());
                     
                     if self.verbose {
-                        println!("  {} {}", "Type:".dimmed(), service.service_type.cyan());
+                        println!("  {} {}", "Kind:".dimmed(), service.kind.name().cyan());
+                        if let Some(label) = &service.service_type {
+                            println!("  {} {}", "Type:".dimmed(), label.cyan());
+                        }
                         println!("  {} {}", "Command:".dimmed(), service.command.dimmed());
                         
                         if let Some(dir) = &service.working_dir {
@@ -364,31 +923,8 @@ impl StartArgs {
                         }
                     }
                     
-                    // RESOLVE the working directory relative to project base
-                    let working_dir = if let Some(service_dir) = &service.working_dir {
-                        project.resolve_path(service_dir).to_string_lossy().to_string()
-                    } else {
-                        // Default to project base directory
-                        project.base_path.as_ref()
-                            .map(|p| p.to_string_lossy().to_string())
-                            .unwrap_or_else(|| ".".to_string())
-                    };
-                    
-                    let child = self.spawn_service_command(service, &env_vars, &working_dir).await?;
-                    let pid = child.id();
-
-                    process_state.add_process(child, &service.name, &project.name, &service.command)?;
-                    
-                    println!("{} {} {} {}", 
-                        "✓".green(), 
-                        format!("Started service: {}", service.name).bold(),
-                        format!("(PID: {})", pid).dimmed(),
-                        format!("in directory: {}", working_dir).blue()
-                    );
-
-                    if let Some(health_check) = &service.health_check {
-                        self.wait_for_health_check(service, health_check).await?;
-                    }
+                    self.launch_service(project, service, project_env, process_state, &mut fingerprints).await?;
+                    ready.insert(service.name.as_str());
                     
                     if self.verbose {
                         println!("  {} {}", "Status:".dimmed(), "RUNNING".green());
@@ -401,6 +937,8 @@ impl StartArgs {
             }
         }
         
+        self.run_project_hook(project, HookStage::PostStart, project_env).await?;
+
         println!("{}", "─".repeat(50).dimmed());
         println!("{}", "All services started successfully!".green().bold());
         println!("{}", format!("Tracking {} processes in memory", process_state.process_count()).dimmed());
@@ -408,16 +946,17 @@ impl StartArgs {
         Ok(())
     }
 
-    async fn start_in_background(&self, project: ProjectConfig) -> Result<()> {
+    async fn start_in_background(&self, project: ProjectConfig, project_env: &ResolvedEnv) -> Result<()> {
         println!("{}", format!("Starting project '{}' in background mode...", project.name).bold());
 
         if self.verbose {
             self.show_verbose_configuration(&project);
         }
 
-        // Pre-collect all the services we need to start
+        // Pre-collect all the services we need to start, jobs before their dependents
         let services_to_start: Vec<Service> = if let Some(services) = &project.services {
-            services.iter()
+            self.sort_services_by_dependencies(services)
+                .into_iter()
                 .filter(|service| self.should_start_service(service))
                 .cloned()
                 .collect()
@@ -430,7 +969,6 @@ impl StartArgs {
             return Ok(());
         }
 
-        let env_vars = project.environment.clone().unwrap_or_default();
         let project_name = project.name.clone();
 
         if self.verbose {
@@ -441,11 +979,29 @@ impl StartArgs {
             println!();
         }
 
+        self.run_project_hook(&project, HookStage::PreStart, project_env).await?;
+        let mut fingerprints = FingerprintStore::load(&project.name)?;
+
         // Get ONE global state instance for the entire operation
         let mut process_state = get_global_state();
         
         // Start each service and track it
         for service in services_to_start {
+            match service.kind {
+                ServiceKind::External => {
+                    self.check_external_service(&project, &service, project_env).await?;
+                    continue;
+                }
+                ServiceKind::Oneshot => {
+                    self.run_oneshot(&project, &service, project_env, &mut fingerprints).await?;
+                    continue;
+                }
+                ServiceKind::Container => {
+                    self.launch_container(&project, &service, project_env, &mut fingerprints, None).await?;
+                    continue;
+                }
+                ServiceKind::Process => {}
+            }
             println!("{}", format!("Starting background service: {}", service.name).bold());
             
             if self.verbose {
@@ -461,38 +1017,58 @@ impl StartArgs {
                     .unwrap_or_else(|| ".".to_string())
             };
             
-            match self.spawn_service_command(&service, &env_vars, &working_dir).await {
-                Ok(child) => {
-                    let pid = child.id();
-                    
-                    // Add to the SAME global state instance (no race condition)
-                    match process_state.add_process(child, &service.name, &project_name, &service.command) {
-                        Ok(()) => {
-                            println!("{} {} {} {}", 
-                                "✓".green(), 
-                                format!("Started background service: {}", service.name).bold(),
-                                format!("(PID: {})", pid).dimmed(),
-                                format!("in directory: {}", working_dir).blue()
-                            );
-                        }
-                        Err(e) => {
-                            eprintln!("{} {}", "ERROR:".red(), format!("Failed to track service {}: {}", service.name, e).red());
-                            // Kill the process since we failed to track it
-                            let _ = std::process::Command::new("kill")
-                                .arg(pid.to_string())
-                                .output();
+            let env = self.service_env(&project, &service, project_env)?;
+            let command = env.redactor().redact(&service.command);
+            run_setup(&project, &service, &env, &mut fingerprints, false).await?;
+            self.run_service_hook(&project, &service, &working_dir, HookStage::PreStart, &env).await?;
+            for replica in 0..service.replicas {
+                let label = replica_label(&service.name, replica, service.replicas);
+                let replica_env = replica_env(&service, &env, replica);
+                let log = (service.replicas > 1).then(|| log_path(&project_name, &service.name, replica));
+                match self.spawn_service_command(&project_name, &service, replica, &replica_env, &working_dir, log.as_deref()).await {
+                    Ok(child) => {
+                        let pid = child.id();
+                        
+                        // Add to the SAME global state instance (no race condition)
+                        match process_state.add_process(child, &service.name, replica, &project_name, &command) {
+                            Ok(()) => {
+                                if let Err(e) = RunRecord::record_service(&project_name, project.config_path.as_deref(), &service.name, replica, pid, &command) {
+                                    eprintln!("{} {}", "WARN:".yellow(), format!("Failed to record service {}: {}", label, e).yellow());
+                                }
+                                println!("{} {} {} {}", 
+                                    "✓".green(), 
+                                    format!("Started background service: {}", label).bold(),
+                                    format!("(PID: {})", pid).dimmed(),
+                                    format!("in directory: {}", working_dir).blue()
+                                );
+                                if let Some(log) = &log {
+                                    println!("  {} {}", "Logs:".dimmed(), log.display().to_string().dimmed());
+                                }
+                            }
+                            Err(e) => {
+                                eprintln!("{} {}", "ERROR:".red(), format!("Failed to track service {}: {}", label, e).red());
+                                // Kill the process since we failed to track it
+                                let _ = std::process::Command::new("kill")
+                                    .arg(pid.to_string())
+                                    .output();
+                            }
                         }
                     }
-                }
-                Err(e) => {
-                    eprintln!("{} {}", "ERROR".red(), format!("Failed to start service {}: {}", service.name, e).red());
+                    Err(e) => {
+                        eprintln!("{} {}", "ERROR".red(), format!("Failed to start service {}: {}", label, e).red());
+                    }
                 }
             }
+            if process_state.is_service_running(&project_name, &service.name) {
+                self.run_service_hook(&project, &service, &working_dir, HookStage::PostStart, &env).await?;
+            }
             
             // Small delay between service starts
             tokio::time::sleep(std::time::Duration::from_millis(300)).await;
         }
 
+        self.run_project_hook(&project, HookStage::PostStart, project_env).await?;
+
         // Show final count before releasing the lock
         let final_count = process_state.process_count();
         println!("{} {}", "SUCCESS:".green(), format!("Project '{}' successfully started in background mode", project_name).bold());
@@ -534,17 +1110,44 @@ impl StartArgs {
         sorted.push(service);
     }
 
-    async fn wait_for_dependencies(&self, service: &Service, process_state: &ProcessState, project_name: &str) -> Result<()> {
+    /// Checks that every dependency of `service` is ready: started, checked
+    /// or (for jobs) completed earlier in this start, or left running or
+    /// completed by a previous one. External services outside the selection
+    /// are probed once; other unmet dependencies are only warned about.
+    async fn check_dependencies(&self, project: &ProjectConfig, service: &Service, ready: &HashSet<&str>) -> Result<()> {
+        let record = RunRecord::load(&project.name)?;
         for dep_name in &service.dependencies {
-            if !process_state.is_service_running(project_name, dep_name) {
-                println!("{}", format!("Waiting for dependency: {} → {}", service.name, dep_name).dimmed());
+            let done_before = record.as_ref().is_some_and(|record| {
+                record.alive_services().iter().any(|s| &s.service_name == dep_name)
+                    || record.job(dep_name).is_some_and(|job| job.succeeded())
+            });
+            if ready.contains(dep_name.as_str()) || done_before {
                 if self.verbose {
-                    println!("  {} {}", "Dependency not yet ready:".yellow(), dep_name);
+                    println!("  {} {}", "Dependency ready:".green(), dep_name);
                 }
-                tokio::time::sleep(std::time::Duration::from_secs(1)).await;
-            } else if self.verbose {
-                println!("  {} {}", "Dependency ready:".green(), dep_name);
+                continue;
             }
+
+            let dep = project.services.iter().flatten().find(|s| &s.name == dep_name);
+            let last_job = record.as_ref().and_then(|record| record.job(dep_name));
+            let problem = match dep.map(|dep| (dep.kind, dep)) {
+                Some((ServiceKind::Oneshot, _)) => match last_job {
+                    Some(job) => format!("its last run failed with exit code {}", job.exit_code),
+                    None => "it has not completed in this or an earlier start".to_string(),
+                },
+                // Not selected this time, but may well be up anyway
+                Some((ServiceKind::External, dep)) => {
+                    let Some(check) = &dep.health_check else { continue };
+                    match probe(&check.resolve(&external_vars(dep))).await {
+                        Ok(()) => continue,
+                        Err(error) => format!("it is not reachable ({})", error),
+                    }
+                }
+                _ => "it is not running".to_string(),
+            };
+            eprintln!("{} {}", "WARN:".yellow(), format!(
+                "{} depends on {}, but {}", service.name, dep_name, problem
+            ).yellow());
         }
         Ok(())
     }
@@ -554,40 +1157,42 @@ impl StartArgs {
 
         match health_check.type_entry.as_str() {
             "http" => {
-                self.wait_for_http_health_check(health_check).await?;
+                println!("   {} {}: {}", "🌐".cyan(), "HTTP check".to_string().dimmed(), health_check.http_target.to_string().cyan().bold());
             }
             "port" => {
-                self.wait_for_port_health_check(health_check).await?;
+                if let Some(port) = health_check.port {
+                    println!("   {}", format!("Port check: {}", port).dimmed());
+                }
             }
             _ => {
-                println!("{}", format_args!("Unrecognized health check type: {}", health_check.type_entry))
+                println!("{}", format_args!("Unrecognized health check type: {}", health_check.type_entry));
+                return Ok(());
             }
         }
+        wait_until_healthy(&service.name, health_check).await?;
 
         println!("{} {}", "✓".green(), format!("Health check passed: {}", service.name).bold());
         Ok(())
     }
 
-    async fn wait_for_http_health_check(&self, health_check: &crate::configs::yaml_parser::HealthCheck) -> Result<()> {
-        println!("   {} {}: {}", "🌐".cyan(), "HTTP check".to_string().dimmed(), health_check.http_target.to_string().cyan().bold());
-        tokio::time::sleep(std::time::Duration::from_secs(2)).await;
-        Ok(())
-    }
-
-    async fn wait_for_port_health_check(&self, health_check: &crate::configs::yaml_parser::HealthCheck) -> Result<()> {
-        if let Some(port) = health_check.port {
-            println!("   {}", format!("Port check: {}", port).dimmed()); 
-            tokio::time::sleep(std::time::Duration::from_secs(1)).await;
+    fn validate_args(&self) -> Result<()> {
+        if self.name.is_some() && self.file.is_some() {
+            return Err(ToolError::ConfigError(
+                format!("{} Cannot use both a project name and --file", "ERROR:".red())
+            ));
         }
-        Ok(())
-    }
 
-    fn validate_args(&self) -> Result<()> {
         if self.only.is_some() && self.skip.is_some() {
             return Err(ToolError::ConfigError(
                 format!("{} Cannot use both --only and --skip filters simultaneously", "ERROR:".red())
             ));
         }
+
+        if self.watch && self.background {
+            return Err(ToolError::ConfigError(
+                format!("{} --watch needs to stay attached and cannot be used with --background", "ERROR:".red())
+            ));
+        }
         
         // Validate service names in filters
         if let Some(only_services) = &self.only {
@@ -612,4 +1217,78 @@ impl StartArgs {
         
         Ok(())
     }
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use clap::Parser;
+
+    #[derive(Parser)]
+    struct Cli {
+        #[command(flatten)]
+        start: StartArgs,
+    }
+
+    fn project(dir: &std::path::Path, name: &str, migrate: &str) -> ProjectConfig {
+        let config_path = dir.join("devspin.yaml");
+        std::fs::write(&config_path, format!(r#"
+name: {}
+services:
+  - {{ name: migrate, kind: oneshot, command: "{}", dependencies: [] }}
+  - {{ name: seed, kind: oneshot, command: "touch seeded", dependencies: [migrate] }}
+"#, name, migrate)).unwrap();
+        ProjectConfig::load(&config_path.to_string_lossy(), None).unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_failing_job_aborts_start_before_dependents() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let start = Cli::parse_from(["devspin"]).start;
+        let project = project(dir.path(), "start-job-fails", "sh -c 'exit 3'");
+
+        let err = start.start_services(&project, &ResolvedEnv::default(), &mut ProcessState::new()).await.unwrap_err();
+        assert!(err.to_string().contains("Job 'migrate' exited with code 3"));
+        assert!(!dir.path().join("seeded").exists());
+        RunRecord::discard_if_idle(&project.name).unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_dependents_start_after_a_completed_job() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let start = Cli::parse_from(["devspin"]).start;
+        let project = project(dir.path(), "start-job-completes", "true");
+
+        start.start_services(&project, &ResolvedEnv::default(), &mut ProcessState::new()).await.unwrap();
+        assert!(dir.path().join("seeded").exists());
+        let record = RunRecord::load(&project.name).unwrap().unwrap();
+        assert!(record.job("migrate").unwrap().succeeded());
+        assert!(record.job("seed").unwrap().succeeded());
+        RunRecord::discard_if_idle(&project.name).unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_restarting_a_container_stops_it_through_the_record() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let config_path = dir.path().join("devspin.yaml");
+        std::fs::write(&config_path, r#"
+name: start-container-restart
+services:
+  - { name: cache, kind: container, image: "redis:7", hooks: { pre_stop: "touch stopping" }, dependencies: [] }
+"#).unwrap();
+        let project = ProjectConfig::load(&config_path.to_string_lossy(), None).unwrap();
+        let service = &project.services.as_ref().unwrap()[0];
+        let start = Cli::parse_from(["devspin"]).start;
+        let mut fingerprints = FingerprintStore::load(&project.name).unwrap();
+
+        start.launch_container(&project, service, &ResolvedEnv::default(), &mut fingerprints, None).await.unwrap();
+        let record = RunRecord::load(&project.name).unwrap().unwrap();
+        let id = record.services[0].container_id.clone().unwrap();
+        assert!(containers::is_running(&id));
+
+        start.stop_service(&project, service, &ResolvedEnv::default(), &mut ProcessState::new()).await.unwrap();
+        assert!(!containers::is_running(&id));
+        assert!(dir.path().join("stopping").exists());
+        assert!(RunRecord::load(&project.name).unwrap().is_none());
+    }
+}
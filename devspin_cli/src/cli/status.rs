@@ -10,6 +10,13 @@ use crossterm::{
 use std::io;
 use crate::ProcessInfo;
 use crate::error::Result;
+use crate::configs::yaml_parser::ProjectConfig;
+use crate::process::containers;
+use crate::process::health::{external_vars, probe};
+use crate::process::jobs::format_duration;
+use crate::process::limits::{exit_reason, read_exit};
+use crate::process::replicas::{log_path, replica_label, tail_lines};
+use crate::process::runs::{JobRecord, RunRecord};
 
 #[derive(Debug, Args, Clone)]
 pub struct StatusArgs {
@@ -64,8 +71,11 @@ impl StatusArgs {
             return Ok(());
         }
 
-        for service in &services {
-            self.print_service_state(service).await?;
+        for group in services.chunk_by(|a, b| a.project == b.project && a.name == b.name) {
+            match group {
+                [service] if service.replica == 0 => self.print_service_state(service).await?,
+                replicas => self.print_replica_group(replicas),
+            }
             println!();
         }
 
@@ -91,11 +101,17 @@ impl StatusArgs {
         // Current status
         println!("  {}: {}", "Status".dimmed(), self.format_status(&service.status));
         
-        // Process info
-        println!("  {}: {} | {}", "Process".dimmed(), 
-            format!("PID: {}", service.pid).yellow(),
-            self.format_uptime(service.start_time).cyan()
-        );
+        // Process info; jobs have no process left and external services none of ours
+        if service.status == ServiceStatus::External {
+            println!("  {}: {}", "Process".dimmed(), "not managed by devspin".dimmed());
+        } else if service.is_job() {
+            println!("  {}: {} ago", "Ran".dimmed(), self.format_uptime(service.start_time).cyan());
+        } else {
+            println!("  {}: {} | {}", "Process".dimmed(), 
+                service.handle().yellow(),
+                self.format_uptime(service.start_time).cyan()
+            );
+        }
 
         // Last output/activity
         if let Some(last_output) = &service.last_output {
@@ -132,6 +148,41 @@ impl StatusArgs {
         Ok(())
     }
 
+    /// One entry for a replicated service, with a line per replica.
+    fn print_replica_group(&self, replicas: &[LiveServiceState]) {
+        let first = &replicas[0];
+        let healthy = replicas.iter().filter(|r| r.health == ServiceHealth::Healthy).count();
+        let status_badge = if healthy == replicas.len() {
+            "[HEALTHY]".green().bold()
+        } else {
+            "[DEGRADED]".yellow().bold()
+        };
+
+        println!("{} {} ({}) {}",
+            status_badge,
+            first.name.bold().white(),
+            first.project.blue(),
+            format!("{}/{} replicas healthy", healthy, replicas.len()).dimmed()
+        );
+
+        for replica in replicas {
+            println!("  {} {} | {} | {}",
+                format!("#{}", replica.replica).cyan(),
+                self.format_status(&replica.status),
+                replica.handle().yellow(),
+                self.format_uptime(replica.start_time).cyan()
+            );
+            if let Some(last_output) = &replica.last_output {
+                println!("     {}", last_output.truncate(60).dimmed());
+            }
+            if self.logs {
+                for log in replica.recent_logs.iter().take(self.tail) {
+                    println!("     {}", log.dimmed());
+                }
+            }
+        }
+    }
+
     async fn follow_mode(&self) -> Result<()> {
         // Clear screen and setup
         execute!(io::stdout(), Clear(ClearType::All), MoveTo(0, 0), Hide)?;
@@ -149,7 +200,7 @@ impl StatusArgs {
             let services = self.get_active_services().await?;
             
             for service in &services {
-                self.print_live_service_state(service).await?;
+                self.print_live_service_state(service, &services).await?;
             }
 
             // Show summary in follow mode
@@ -174,7 +225,7 @@ impl StatusArgs {
         Ok(())
     }
 
-    async fn print_live_service_state(&self, service: &LiveServiceState) -> Result<()> {
+    async fn print_live_service_state(&self, service: &LiveServiceState, all: &[LiveServiceState]) -> Result<()> {
         let status_indicator = match service.health {
             ServiceHealth::Healthy => "●".green(),
             ServiceHealth::Unhealthy => "●".red(),
@@ -189,11 +240,16 @@ impl StatusArgs {
             ServiceHealth::Unknown => "UNKNOWN".white().dimmed(),
         };
 
+        let process = match &service.status {
+            ServiceStatus::External => "external".to_string(),
+            _ if service.is_job() => "job".to_string(),
+            _ => service.handle(),
+        };
         println!("{} {:<20} {:<12} {:<8} {}",
             status_indicator,
-            service.name.bold(),
+            service.label(all).bold(),
             status_text,
-            format!("PID:{}", service.pid).dimmed(),
+            process.dimmed(),
             self.format_uptime(service.start_time).cyan()
         );
 
@@ -238,7 +294,7 @@ impl StatusArgs {
         for service in &services {
             println!("{} {} ({})", 
                 "LOG".cyan().bold(), 
-                service.name.bold(), 
+                service.label(&services).bold(), 
                 service.project.blue()
             );
             println!("{}", "-".repeat(40).dimmed());
@@ -281,10 +337,123 @@ impl StatusArgs {
             
             active_services.push(live_service);
         }
-        
+
+        active_services.extend(self.exited_services());
+        active_services.extend(self.job_services());
+        active_services.extend(self.external_services().await);
+
+        // Keep replicas of a service next to each other
+        active_services.sort_by(|a, b| (&a.project, &a.name, a.replica).cmp(&(&b.project, &b.name, b.replica)));
         Ok(active_services)
     }
 
+    /// Limited services that died on their own since they were started, with
+    /// the reason (e.g. "killed: memory limit") as their error.
+    fn exited_services(&self) -> Vec<LiveServiceState> {
+        let mut exited = Vec::new();
+
+        for record in RunRecord::all() {
+            if self.project_name.as_ref().is_some_and(|name| name != &record.project) {
+                continue;
+            }
+            let config = recorded_config(&record);
+
+            for service in record.services.iter().filter(|service| !service.is_alive()) {
+                let Some(code) = read_exit(&record.project, &service.service_name, service.replica) else { continue };
+                let limits = config.as_ref()
+                    .and_then(|config| config.services.iter().flatten().find(|s| s.name == service.service_name))
+                    .and_then(|s| s.limits.as_ref());
+
+                let mut live = self.convert_to_live_state(&service.to_process_info(&record.project));
+                let reason = exit_reason(code, limits, &live.recent_logs);
+                live.status = ServiceStatus::Error(reason.clone());
+                live.health = ServiceHealth::Unhealthy;
+                live.last_error = Some(reason);
+                exited.push(live);
+            }
+        }
+        exited
+    }
+
+    /// Oneshot services of the current runs, as Completed or Failed.
+    fn job_services(&self) -> Vec<LiveServiceState> {
+        RunRecord::all()
+            .into_iter()
+            .filter(|record| self.project_name.as_ref().is_none_or(|name| name == &record.project))
+            .flat_map(|record| {
+                record.jobs.iter().map(|job| self.job_state(&record.project, job)).collect::<Vec<_>>()
+            })
+            .filter(|job| !self.errors || job.health == ServiceHealth::Unhealthy)
+            .collect()
+    }
+
+    /// External services the current runs waited for, probed now.
+    async fn external_services(&self) -> Vec<LiveServiceState> {
+        let mut externals = Vec::new();
+
+        for record in RunRecord::all() {
+            if record.externals.is_empty() || self.project_name.as_ref().is_some_and(|name| name != &record.project) {
+                continue;
+            }
+            let Some(config) = recorded_config(&record) else { continue };
+
+            for service in config.services.iter().flatten().filter(|s| record.externals.contains(&s.name)) {
+                let result = match &service.health_check {
+                    Some(check) => probe(&check.resolve(&external_vars(service))).await,
+                    None => Err("no health check".to_string()),
+                };
+                let (health, last_error) = match result {
+                    Ok(()) => (ServiceHealth::Healthy, None),
+                    Err(error) => (ServiceHealth::Unhealthy, Some(format!("unreachable: {}", error))),
+                };
+                if self.errors && health != ServiceHealth::Unhealthy {
+                    continue;
+                }
+
+                externals.push(LiveServiceState {
+                    name: service.name.clone(),
+                    replica: 0,
+                    project: record.project.clone(),
+                    pid: 0,
+                    container_id: None,
+                    status: ServiceStatus::External,
+                    health,
+                    start_time: std::time::SystemTime::now(),
+                    last_output: None,
+                    last_error,
+                    recent_logs: Vec::new(),
+                    resource_usage: None,
+                });
+            }
+        }
+        externals
+    }
+
+    fn job_state(&self, project: &str, job: &JobRecord) -> LiveServiceState {
+        let recent_logs = tail_lines(&log_path(project, &job.service_name, 0), self.tail);
+        let (status, health, last_error) = if job.succeeded() {
+            (ServiceStatus::Completed(job.duration()), ServiceHealth::Healthy, None)
+        } else {
+            let error = format!("job exited with code {}", job.exit_code);
+            (ServiceStatus::Failed(job.duration()), ServiceHealth::Unhealthy, Some(error))
+        };
+
+        LiveServiceState {
+            name: job.service_name.clone(),
+            replica: 0,
+            project: project.to_string(),
+            pid: 0,
+            container_id: None,
+            status,
+            health,
+            start_time: std::time::UNIX_EPOCH + std::time::Duration::from_secs(job.started_at),
+            last_output: recent_logs.last().cloned(),
+            last_error,
+            recent_logs,
+            resource_usage: None,
+        }
+    }
+
     fn convert_to_live_state(&self, process_info: &ProcessInfo) -> LiveServiceState {
         // Determine health based on actual process state
         let health = match &process_info.status {
@@ -297,10 +466,21 @@ impl StatusArgs {
             crate::ProcessStatus::Error(_) => ServiceHealth::Unhealthy,
         };
 
+        // Replicas write to log files; single services still use the terminal
+        let recent_logs = match &process_info.container_id {
+            Some(id) => containers::logs(id, self.tail),
+            None => tail_lines(
+                &log_path(&process_info.project_name, &process_info.service_name, process_info.replica),
+                self.tail,
+            ),
+        };
+
         LiveServiceState {
             name: process_info.service_name.clone(),
+            replica: process_info.replica,
             project: process_info.project_name.clone(),
             pid: process_info.pid,
+            container_id: process_info.container_id.clone(),
             status: match &process_info.status {
                 crate::ProcessStatus::Running => ServiceStatus::Running,
                 crate::ProcessStatus::Stopped => ServiceStatus::Stopped,
@@ -308,12 +488,12 @@ impl StatusArgs {
             },
             health,
             start_time: process_info.start_time,
-            last_output: None, // You'd need to capture this from process output
+            last_output: recent_logs.last().cloned(),
             last_error: match &process_info.status {
                 crate::ProcessStatus::Error(err) => Some(err.clone()),
                 _ => None,
             },
-            recent_logs: Vec::new(), // You'd need to capture process stdout/stderr
+            recent_logs,
             resource_usage: None, // You could implement this with system calls
         }
     }
@@ -325,9 +505,12 @@ impl StatusArgs {
         let healthy = services.iter().filter(|s| s.health == ServiceHealth::Healthy).count();
         let unhealthy = services.iter().filter(|s| s.health == ServiceHealth::Unhealthy).count();
         let starting = services.iter().filter(|s| s.health == ServiceHealth::Starting).count();
-        let total = services.len();
+        let total = services.chunk_by(|a, b| a.project == b.project && a.name == b.name).count();
 
         println!("  {}: {}", "Total Services".dimmed(), total);
+        if services.len() != total {
+            println!("  {}: {}", "Processes".dimmed(), services.len());
+        }
         println!("  {}: {}", "Healthy".green(), healthy);
         println!("  {}: {}", "Unhealthy".red(), unhealthy);
         if starting > 0 {
@@ -352,6 +535,9 @@ impl StatusArgs {
             ServiceStatus::Stopped => "Stopped".red(),
             ServiceStatus::Error(_) => "Error".red().bold(),
             ServiceStatus::Restarting => "Restarting".yellow(),
+            ServiceStatus::External => "External".cyan(),
+            ServiceStatus::Completed(duration) => format!("Completed in {}", format_duration(*duration)).green(),
+            ServiceStatus::Failed(duration) => format!("Failed after {}", format_duration(*duration)).red().bold(),
         }
     }
 
@@ -374,12 +560,20 @@ impl StatusArgs {
     }
 }
 
+/// The config a run was started from, with the profile it was started with.
+fn recorded_config(record: &RunRecord) -> Option<ProjectConfig> {
+    let path = record.config_path.as_ref()?;
+    ProjectConfig::load(&path.to_string_lossy(), record.inputs.profile.as_deref()).ok()
+}
+
 // Data structures for live service state
 #[derive(Debug, Clone)]
 pub struct LiveServiceState {
     pub name: String,
+    pub replica: u32,
     pub project: String,
     pub pid: u32,
+    pub container_id: Option<String>,
     pub status: ServiceStatus,
     pub health: ServiceHealth,
     pub start_time: std::time::SystemTime,
@@ -389,6 +583,27 @@ pub struct LiveServiceState {
     pub resource_usage: Option<ResourceUsage>,
 }
 
+impl LiveServiceState {
+    /// Service name, with `#<index>` when it runs as several replicas.
+    fn label(&self, all: &[LiveServiceState]) -> String {
+        let replicas = all.iter().filter(|s| s.project == self.project && s.name == self.name).count();
+        replica_label(&self.name, self.replica, replicas as u32)
+    }
+
+    /// `PID: 123`, or the short container ID for container services.
+    fn handle(&self) -> String {
+        match &self.container_id {
+            Some(id) => format!("container: {}", containers::short_id(id)),
+            None => format!("PID: {}", self.pid),
+        }
+    }
+
+    /// A oneshot service that has finished, rather than a process.
+    fn is_job(&self) -> bool {
+        matches!(self.status, ServiceStatus::Completed(_) | ServiceStatus::Failed(_))
+    }
+}
+
 #[derive(Debug, Clone, PartialEq)]
 pub enum ServiceStatus {
     Running,
@@ -396,6 +611,12 @@ pub enum ServiceStatus {
     Stopped,
     Error(String),
     Restarting,
+    /// Not run by devspin; only its health is known
+    External,
+    /// A oneshot service that exited successfully, with how long it ran
+    Completed(std::time::Duration),
+    /// A oneshot service that exited non-zero, with how long it ran
+    Failed(std::time::Duration),
 }
 
 #[derive(Debug, Clone, PartialEq)]
@@ -1,8 +1,15 @@
 use clap::Args;
 use colored::*;
+use crate::configs::env::{resolve_project_env, resolve_service_env, ResolvedEnv};
+use crate::configs::yaml_parser::ProjectConfig;
 use crate::error::{Result, ToolError};
+use crate::process::hooks::{run_hook, HookContext, HookStage};
 use crate::process::manager::ProcessManager;
+use crate::process::replicas::replica_label;
 use crate::process::global::get_global_state;
+use crate::process::containers;
+use crate::process::ports::{assign_ports, inject_service_ports};
+use crate::process::runs::RunRecord;
 use crate::ProcessInfo;
 use std::time::{Duration, Instant};
 use tokio::time::sleep;
@@ -41,6 +48,14 @@ pub struct StopArgs {
     pub dry_run: bool,
 }
 
+/// Config and environment of the project being stopped, for running hooks.
+struct HookTarget {
+    project: ProjectConfig,
+    env: ResolvedEnv,
+    /// `--set` overrides the run was started with
+    overrides: Vec<(String, String)>,
+}
+
 impl StopArgs {
     pub async fn execute(&self) -> Result<()> {
         self.validate_args()?;
@@ -106,12 +121,16 @@ impl StopArgs {
         println!("{} {}", "Stopping project:".bright_red().bold(), project_name.bold());
         
         let services = self.get_services_for_project(project_name);
+        self.note_external_services(project_name);
         
         if services.is_empty() {
+            // Nothing to signal; forget any jobs or external services it recorded
+            RunRecord::discard_if_idle(project_name)?;
             println!("{}", "No running services found for this project".yellow());
             return Ok(());
         }
         
+        let running_count = services.len();
         let services_to_stop: Vec<_> = services
             .into_iter()
             .filter(|service| self.should_stop_service(service))
@@ -125,27 +144,119 @@ impl StopArgs {
         if self.verbose {
             self.show_stop_plan(&services_to_stop);
         }
+
+        // Project hooks only run when the whole project goes down
+        let whole_project = services_to_stop.len() == running_count;
+        let target = self.hook_target(project_name);
+
+        if let (Some(target), true) = (&target, whole_project) {
+            self.run_project_hook(target, HookStage::PreStop).await?;
+        }
         
         // Stop services in reverse dependency order
         let sorted_services = self.sort_services_for_shutdown(&services_to_stop);
         
-        self.stop_services_gracefully(&sorted_services).await?;
+        self.stop_services_gracefully(&sorted_services, target.as_ref()).await?;
+
+        if let (Some(target), true) = (&target, whole_project) {
+            self.run_project_hook(target, HookStage::PostStop).await?;
+        }
         
         println!("{} {}", "✓".green(), format!("Project '{}' stopped successfully", project_name).bold());
         Ok(())
     }
     
-    async fn stop_services_gracefully(&self, services: &[ProcessInfo]) -> Result<()> {
+    /// External services are never signalled; say so rather than skip them silently.
+    fn note_external_services(&self, project_name: &str) {
+        let Some(record) = RunRecord::load(project_name).ok().flatten() else { return };
+        for name in &record.externals {
+            println!("  {} {}", "-".dimmed(), format!("Leaving external service {} alone (not managed by devspin)", name).dimmed());
+        }
+    }
+
+    /// Loads the config recorded at start time, with the profile, env file
+    /// and overrides it was started with, so stop hooks see what start hooks saw.
+    fn hook_target(&self, project_name: &str) -> Option<HookTarget> {
+        let record = RunRecord::load(project_name).ok().flatten()?;
+        let config_path = record.config_path?;
+        let inputs = record.inputs;
+        let loaded = ProjectConfig::load(&config_path.to_string_lossy(), inputs.profile.as_deref()).and_then(|project| {
+            let mut env = resolve_project_env(&project, &inputs.env_files())?;
+            // The ports are still held by the run, so this only reads them back
+            assign_ports(&project, &mut env)?;
+            Ok(HookTarget { project, env, overrides: inputs.overrides.clone() })
+        });
+
+        match loaded {
+            Ok(target) => Some(target),
+            Err(e) => {
+                eprintln!("{} {}", "WARN:".yellow(), format!("Skipping stop hooks: {}", e).yellow());
+                None
+            }
+        }
+    }
+
+    async fn run_project_hook(&self, target: &HookTarget, stage: HookStage) -> Result<()> {
+        let working_dir = target.project.resolve_path("");
+        let mut env = target.env.clone();
+        env.apply_overrides(&target.overrides);
+        let ctx = HookContext {
+            project: &target.project.name,
+            service: None,
+            working_dir: &working_dir,
+            env: &env.vars,
+            redactor: &env.redactor(),
+            verbose: self.verbose,
+        };
+        run_hook(target.project.hooks.as_ref(), stage, &ctx).await
+    }
+
+    async fn run_service_hook(&self, target: Option<&HookTarget>, service_name: &str, stage: HookStage) -> Result<()> {
+        let Some(target) = target else { return Ok(()) };
+        let Some(service) = target.project.services.iter().flatten().find(|s| s.name == service_name) else {
+            return Ok(());
+        };
+        if service.hooks.is_none() {
+            return Ok(());
+        }
+
+        let mut env = resolve_service_env(&target.project, service, &target.env, &target.overrides)?;
+        inject_service_ports(service, &mut env);
+        let working_dir = target.project.service_dir(service);
+        let ctx = HookContext {
+            project: &target.project.name,
+            service: Some(&service.name),
+            working_dir: &working_dir,
+            env: &env.vars,
+            redactor: &env.redactor(),
+            verbose: self.verbose,
+        };
+        run_hook(service.hooks.as_ref(), stage, &ctx).await
+    }
+
+    async fn stop_services_gracefully(&self, services: &[ProcessInfo], target: Option<&HookTarget>) -> Result<()> {
         let total_services = services.len();
         let mut stopped_count = 0;
         
-        for service in services {
-            println!("{} {}", "Stopping:".dimmed(), service.service_name.bold());
+        for (index, service) in services.iter().enumerate() {
+            let replicas = services.iter().filter(|s| s.service_name == service.service_name).count();
+            println!("{} {}", "Stopping:".dimmed(), replica_label(&service.service_name, service.replica, replicas as u32).bold());
             
             if self.verbose {
-                println!("  {} {}", "PID:".dimmed(), service.pid);
+                match &service.container_id {
+                    Some(id) => println!("  {} {}", "Container:".dimmed(), containers::short_id(id)),
+                    None => println!("  {} {}", "PID:".dimmed(), service.pid),
+                }
                 println!("  {} {}", "Command:".dimmed(), service.command.dimmed());
             }
+
+            // Replicas sit next to each other; hooks run once around all of them
+            let first_replica = index == 0 || services[index - 1].service_name != service.service_name;
+            let last_replica = services.get(index + 1).is_none_or(|next| next.service_name != service.service_name);
+
+            if first_replica {
+                self.run_service_hook(target, &service.service_name, HookStage::PreStop).await?;
+            }
             
             match self.stop_single_service(service).await {
                 Ok(()) => {
@@ -153,14 +264,14 @@ impl StopArgs {
                     println!("  {} {}", "✓".green(), "Stopped successfully".green());
                     
                     // Update process state
-                    self.remove_process(service.pid);
+                    self.remove_process(service);
                 }
                 Err(e) => {
                     if self.force {
                         println!("  {} {}", "!".yellow(), "Graceful stop failed, forcing...".yellow());
                         self.force_stop_service(service).await?;
                         stopped_count += 1;
-                        self.remove_process(service.pid);
+                        self.remove_process(service);
                     } else {
                         return Err(ToolError::ProcessError(format!(
                             "Failed to stop service {}: {}. Use --force to kill it",
@@ -169,6 +280,10 @@ impl StopArgs {
                     }
                 }
             }
+
+            if last_replica {
+                self.run_service_hook(target, &service.service_name, HookStage::PostStop).await?;
+            }
             
             println!("  {} {}/{} services stopped", 
                 "Progress:".dimmed(), stopped_count, total_services
@@ -180,6 +295,11 @@ impl StopArgs {
     }
     
     async fn stop_single_service(&self, service: &ProcessInfo) -> Result<()> {
+        // docker stop does its own SIGTERM, wait and SIGKILL
+        if let Some(id) = &service.container_id {
+            return containers::stop(id, self.timeout);
+        }
+
         let start_time = Instant::now();
         let timeout = Duration::from_secs(self.timeout);
         
@@ -209,6 +329,9 @@ impl StopArgs {
         if self.verbose {
             println!("  {} Sending SIGKILL...", "FORCE".dimmed());
         }
+        if let Some(id) = &service.container_id {
+            return containers::remove(id);
+        }
         
         self.kill_process(service.pid)?;
         
@@ -232,6 +355,15 @@ impl StopArgs {
         
         // Simple reversal - in a real implementation you'd want proper dependency analysis
         sorted.reverse();
+
+        // Keep replicas of one service together so its hooks run once
+        let mut order: Vec<String> = Vec::new();
+        for service in &sorted {
+            if !order.contains(&service.service_name) {
+                order.push(service.service_name.clone());
+            }
+        }
+        sorted.sort_by_key(|service| order.iter().position(|name| *name == service.service_name));
         
         sorted
     }
@@ -260,7 +392,7 @@ impl StopArgs {
         
         println!("  {}:", "Services to stop:".dimmed());
         for service in services {
-            println!("    • {} (PID: {})", service.service_name, service.pid);
+            println!("    • {} ({})", service.service_name, service.handle());
         }
         println!();
     }
@@ -289,7 +421,7 @@ impl StopArgs {
                 println!("  {}:", project.bold());
                 for service in services_to_stop {
                     let stop_type = if self.force { "FORCE STOP" } else { "Graceful stop" };
-                    println!("    • {} (PID: {}) - {}", service.service_name, service.pid, stop_type);
+                    println!("    • {} ({}) - {}", service.service_name, service.handle(), stop_type);
                 }
             }
         } else if let Some(project_name) = &self.project_name {
@@ -304,7 +436,7 @@ impl StopArgs {
             
             for service in services_to_stop {
                 let stop_type = if self.force { "FORCE STOP".red() } else { "Graceful stop".green() };
-                println!("  • {} (PID: {}) - {}", service.service_name, service.pid, stop_type);
+                println!("  • {} ({}) - {}", service.service_name, service.handle(), stop_type);
             }
         }
         
@@ -362,8 +494,16 @@ impl StopArgs {
             .unwrap_or(false)
     }
     
-    fn remove_process(&self, pid: u32) {
+    fn remove_process(&self, service: &ProcessInfo) {
         let mut state = get_global_state();
-        let _ = state.remove_process(pid);
+        match &service.container_id {
+            Some(id) => {
+                let _ = RunRecord::forget_container(&service.project_name, id);
+            }
+            None => {
+                let _ = state.remove_process(service.pid);
+                let _ = RunRecord::forget_pid(&service.project_name, service.pid);
+            }
+        }
     }
 }
\ No newline at end of file
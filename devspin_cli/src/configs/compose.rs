@@ -0,0 +1,677 @@
+use std::collections::{HashMap, HashSet};
+
+use serde_yaml::{Mapping, Value};
+
+use crate::configs::export::{exported_services, fixed_port_vars, note_devspin_only, plain_environment, Exported};
+use crate::configs::import::{service_mapping, shell_join, Imported};
+use crate::configs::yaml_parser::{HealthCheck, PortSpec, ProjectConfig, Service, ServiceKind};
+use crate::error::{Result, ToolError};
+
+/// Top-level compose keys that need no counterpart in devspin.yaml.
+const IGNORED_TOP_LEVEL: [&str; 3] = ["version", "name", "services"];
+
+/// Converts a parsed compose file into a devspin config. Every compose
+/// service becomes a container service; anything without a devspin
+/// equivalent is listed in `unmapped`.
+pub fn from_compose(compose: &Value, default_name: &str) -> Result<Imported> {
+    let name = compose.get("name").and_then(Value::as_str).unwrap_or(default_name);
+    let services = compose
+        .get("services")
+        .and_then(Value::as_mapping)
+        .ok_or_else(|| ToolError::ConfigError("Compose file has no services".to_string()))?;
+
+    let mut unmapped = Vec::new();
+    if let Some(top) = compose.as_mapping() {
+        for key in top.keys().filter_map(Value::as_str) {
+            // Named volumes are created by docker on first use
+            if !IGNORED_TOP_LEVEL.contains(&key) && key != "volumes" && !key.starts_with("x-") {
+                unmapped.push(format!("{}: not supported", key));
+            }
+        }
+    }
+
+    let mut converted = Vec::new();
+    let mut waits = Vec::new();
+    for (service_name, spec) in services {
+        let service_name = service_name.as_str().unwrap_or_default();
+        let spec = spec.as_mapping().cloned().unwrap_or_default();
+        converted.push(convert_service(name, service_name, &spec, &mut waits, &mut unmapped)?);
+    }
+
+    // Conditions on dependencies can only be checked once every service is converted
+    for (service, dependency, condition) in waits {
+        let has_health_check = converted.iter().any(|s| {
+            s.get("name").and_then(Value::as_str) == Some(dependency.as_str()) && s.contains_key("health_check")
+        });
+        match condition.as_str() {
+            "service_healthy" if !has_health_check => unmapped.push(format!(
+                "services.{}.depends_on.{}: waits for health, but {} has no health check devspin can run", service, dependency, dependency
+            )),
+            "service_completed_successfully" => unmapped.push(format!(
+                "services.{}.depends_on.{}: devspin waits for the container to start, not to finish", service, dependency
+            )),
+            _ => {}
+        }
+    }
+
+    Imported::new(name, converted, unmapped)
+}
+
+fn convert_service(
+    project: &str,
+    name: &str,
+    spec: &Mapping,
+    waits: &mut Vec<(String, String, String)>,
+    unmapped: &mut Vec<String>,
+) -> Result<Mapping> {
+    let mut service = service_mapping(name);
+    service.insert("kind".into(), "container".into());
+    let mut note = |key: &str, problem: &str| unmapped.push(format!("services.{}.{}: {}", name, key, problem));
+
+    let image = match (spec.get("image").and_then(Value::as_str), spec.contains_key("build")) {
+        (Some(image), build) => {
+            if build {
+                note("build", "not supported; devspin runs the image without building it");
+            }
+            image.to_string()
+        }
+        (None, true) => {
+            // Compose's own name for images it builds
+            let image = format!("{}-{}", project, name);
+            note("build", &format!("not supported; build the image as {} first (e.g. docker compose build)", image));
+            image
+        }
+        (None, false) => {
+            return Err(ToolError::ConfigError(format!("Compose service '{}' has neither image nor build", name)));
+        }
+    };
+    service.insert("image".into(), image.into());
+
+    let mut container_port = None;
+    for (key, value) in spec {
+        let key = key.as_str().unwrap_or_default();
+        match key {
+            "image" | "build" => {}
+            "command" => {
+                if let Some(command) = command_string(value) {
+                    service.insert("command".into(), command.into());
+                }
+            }
+            "depends_on" => {
+                let dependencies = match value {
+                    Value::Sequence(names) => names.iter().filter_map(Value::as_str).map(str::to_string).collect(),
+                    Value::Mapping(conditions) => {
+                        let mut names = Vec::new();
+                        for (dependency, condition) in conditions {
+                            let dependency = dependency.as_str().unwrap_or_default().to_string();
+                            if let Some(condition) = condition.get("condition").and_then(Value::as_str) {
+                                waits.push((name.to_string(), dependency.clone(), condition.to_string()));
+                            }
+                            names.push(dependency);
+                        }
+                        names
+                    }
+                    _ => Vec::new(),
+                };
+                service.insert("dependencies".into(), dependencies.into());
+            }
+            "environment" => {
+                let environment = environment(value, &mut |key| {
+                    note(&format!("environment.{}", key), "has no value (taken from the shell in compose); set it in an env file")
+                });
+                if !environment.is_empty() {
+                    service.insert("environment".into(), Value::Mapping(environment));
+                }
+            }
+            "env_file" => {
+                let files: Vec<Value> = match value {
+                    Value::Sequence(files) => files
+                        .iter()
+                        .filter_map(|file| file.as_str().or_else(|| file.get("path")?.as_str()))
+                        .map(Value::from)
+                        .collect(),
+                    other => other.as_str().map(Value::from).into_iter().collect(),
+                };
+                service.insert("env_file".into(), Value::Sequence(files));
+            }
+            "ports" => {
+                let mut extra = Mapping::new();
+                for port in value.as_sequence().into_iter().flatten() {
+                    let Some((host, target)) = parse_port(port) else {
+                        note("ports", &format!("{} is not supported (only single TCP ports)", render(port)));
+                        continue;
+                    };
+                    if container_port.is_none() {
+                        container_port = Some(target);
+                        service.insert("port".into(), host.map(Value::from).unwrap_or_else(|| "auto".into()));
+                        if host != Some(target) {
+                            service.insert("container_port".into(), target.into());
+                        }
+                    } else {
+                        if host.is_some_and(|host| host != target) {
+                            note("ports", &format!("{} is published as {}:{}; extra ports keep their number", render(port), target, target));
+                        }
+                        extra.insert(format!("p{}", target).into(), target.into());
+                    }
+                }
+                if !extra.is_empty() {
+                    service.insert("ports".into(), Value::Mapping(extra));
+                }
+            }
+            "volumes" => {
+                let mut volumes = Vec::new();
+                for volume in value.as_sequence().into_iter().flatten() {
+                    match volume.as_str() {
+                        Some(volume) => volumes.push(Value::from(volume)),
+                        None => note("volumes", "long syntax is not supported"),
+                    }
+                }
+                service.insert("volumes".into(), Value::Sequence(volumes));
+            }
+            "scale" => {
+                if let Some(replicas) = value.as_u64() {
+                    service.insert("replicas".into(), replicas.into());
+                }
+            }
+            "deploy" => {
+                for (deploy_key, deploy_value) in value.as_mapping().into_iter().flatten() {
+                    match (deploy_key.as_str(), deploy_value.as_u64()) {
+                        (Some("replicas"), Some(replicas)) => {
+                            service.insert("replicas".into(), replicas.into());
+                        }
+                        (deploy_key, _) => note(&format!("deploy.{}", deploy_key.unwrap_or_default()), "not supported"),
+                    }
+                }
+            }
+            "healthcheck" => {}
+            key if key.starts_with("x-") => {}
+            key => note(key, "not supported"),
+        }
+    }
+
+    if let Some(check) = spec.get("healthcheck") {
+        match health_check(check, container_port) {
+            Some(health_check) => {
+                service.insert("health_check".into(), Value::Mapping(health_check));
+            }
+            None if check.get("disable").and_then(Value::as_bool) == Some(true) => {}
+            None => note("healthcheck", "its test has no URL and the service publishes no port to check"),
+        }
+        let timing = ["interval", "timeout", "retries", "start_period"];
+        if timing.iter().any(|key| check.get(*key).is_some()) {
+            note("healthcheck", "timing settings are replaced by devspin's wait (health_check.timeout)");
+        }
+    }
+
+    Ok(service)
+}
+
+/// `command` as one shell string; compose allows a string or an argument list.
+fn command_string(value: &Value) -> Option<String> {
+    match value {
+        Value::String(command) => Some(command.clone()),
+        Value::Sequence(args) => Some(shell_join(&args.iter().map(render).collect::<Vec<_>>())),
+        _ => None,
+    }
+}
+
+/// `environment` as a mapping; compose allows a mapping or `KEY=VALUE` items.
+/// Keys without a value are reported through `missing`.
+fn environment(value: &Value, missing: &mut dyn FnMut(&str)) -> Mapping {
+    let mut environment = Mapping::new();
+    let mut set = |key: &str, value: Option<String>| match value {
+        Some(value) => {
+            environment.insert(key.into(), value.into());
+        }
+        None => missing(key),
+    };
+
+    match value {
+        Value::Mapping(vars) => {
+            for (key, value) in vars {
+                let value = (!value.is_null()).then(|| render(value));
+                set(key.as_str().unwrap_or_default(), value);
+            }
+        }
+        Value::Sequence(items) => {
+            for item in items.iter().filter_map(Value::as_str) {
+                match item.split_once('=') {
+                    Some((key, value)) => set(key, Some(value.to_string())),
+                    None => set(item, None),
+                }
+            }
+        }
+        _ => {}
+    }
+    environment
+}
+
+/// A published port as `(host, container)`, from `"8080:80"`,
+/// `"127.0.0.1:8080:80"`, `"80"` or the long syntax. Ranges and UDP are not
+/// supported.
+fn parse_port(value: &Value) -> Option<(Option<u16>, u16)> {
+    if let Value::Mapping(port) = value {
+        if port.get("protocol").and_then(Value::as_str).is_some_and(|protocol| protocol != "tcp") {
+            return None;
+        }
+        let target = port.get("target")?.as_u64()? as u16;
+        let published = port.get("published").map(render).and_then(|published| published.parse().ok());
+        return Some((published, target));
+    }
+
+    let port = render(value);
+    let port = port.strip_suffix("/tcp").unwrap_or(&port);
+    if port.contains('/') || port.contains('-') {
+        return None;
+    }
+    let parts: Vec<&str> = port.split(':').collect();
+    let target = parts.last()?.parse().ok()?;
+    let host = match parts.len() {
+        1 => None,
+        _ => Some(parts[parts.len() - 2].parse().ok()?),
+    };
+    Some((host, target))
+}
+
+/// An http check when the test command hits a URL (rewritten to the
+/// published port), otherwise a port check on the published port.
+fn health_check(check: &Value, container_port: Option<u16>) -> Option<Mapping> {
+    if check.get("disable").and_then(Value::as_bool) == Some(true) {
+        return None;
+    }
+    let test = match check.get("test")? {
+        Value::Sequence(args) => args.iter().skip(1).map(render).collect::<Vec<_>>().join(" "),
+        other => render(other),
+    };
+
+    let mut health_check = Mapping::new();
+    let url = test
+        .split(|c: char| c.is_whitespace() || c == '"' || c == '\'')
+        .find(|word| word.starts_with("http://") || word.starts_with("https://"));
+
+    match (url, container_port) {
+        (Some(url), port) => {
+            let url = match port {
+                Some(port) => url.replace(&format!(":{}", port), ":${PORT}"),
+                None => url.to_string(),
+            };
+            health_check.insert("type_entry".into(), "http".into());
+            health_check.insert("http_target".into(), url.into());
+        }
+        (None, Some(_)) => {
+            health_check.insert("type_entry".into(), "port".into());
+            health_check.insert("http_target".into(), "".into());
+        }
+        (None, None) => return None,
+    }
+    Some(health_check)
+}
+
+/// Converts a project config into a compose file. Containers keep their
+/// image; processes and jobs are built from their directory (or from
+/// `docker/Dockerfile.<service>`, the layout `devspin init --docker` creates).
+pub fn to_compose(project: &ProjectConfig) -> Result<Exported> {
+    let mut unmapped = Vec::new();
+    if project.hooks.is_some() {
+        unmapped.push("hooks: not exported".to_string());
+    }
+    let base = project.resolve_path("");
+    let services = exported_services(project, &mut unmapped);
+
+    let mut health_checks = HashMap::new();
+    for service in &services {
+        if let Some(check) = &service.health_check {
+            match compose_health_check(service, check) {
+                Some(check) => {
+                    health_checks.insert(service.name.as_str(), check);
+                }
+                None => unmapped.push(format!(
+                    "services.{}.health_check: not converted; checks inside the container need a fixed port", service.name
+                )),
+            }
+        }
+    }
+    let jobs: HashSet<&str> = services.iter().filter(|s| s.kind == ServiceKind::Oneshot).map(|s| s.name.as_str()).collect();
+    let names: HashSet<&str> = services.iter().map(|s| s.name.as_str()).collect();
+
+    let mut compose_services = Mapping::new();
+    let mut named_volumes = Mapping::new();
+    for service in &services {
+        let mut spec = Mapping::new();
+        let dir = service.working_dir.as_deref().unwrap_or("").trim_end_matches('/');
+
+        if service.kind == ServiceKind::Container {
+            spec.insert("image".into(), service.image.clone().unwrap_or_default().into());
+            if !service.command.trim().is_empty() {
+                spec.insert("command".into(), shell_command(&service.command));
+            }
+        } else {
+            let dockerfile = format!("docker/Dockerfile.{}", service.name);
+            let mut build = Mapping::new();
+            if base.join(&dockerfile).exists() {
+                // The Dockerfile's own CMD knows the image layout; devspin's
+                // command assumes the project's
+                build.insert("context".into(), ".".into());
+                build.insert("dockerfile".into(), dockerfile.into());
+            } else {
+                let context = if dir.is_empty() { "." } else { dir };
+                if !base.join(context).join("Dockerfile").exists() {
+                    unmapped.push(format!("services.{}: needs a Dockerfile in {} to build", service.name, context));
+                }
+                build.insert("context".into(), context.into());
+                spec.insert("command".into(), shell_command(&service.command));
+            }
+            spec.insert("build".into(), Value::Mapping(build));
+        }
+
+        let port_vars = fixed_port_vars(service, &mut unmapped);
+        let mut ports = Vec::new();
+        if let Some(PortSpec::Fixed(port)) = service.port {
+            let target = match service.kind {
+                ServiceKind::Container => service.container_port.unwrap_or(port),
+                _ => port,
+            };
+            ports.push(Value::from(format!("{}:{}", port, target)));
+        }
+        for port in service.ports.values() {
+            if let PortSpec::Fixed(port) = port {
+                ports.push(Value::from(format!("{}:{}", port, port)));
+            }
+        }
+        if !ports.is_empty() {
+            spec.insert("ports".into(), Value::Sequence(ports));
+        }
+
+        let mut environment = Mapping::new();
+        for (key, value) in port_vars {
+            environment.insert(key.into(), value.into());
+        }
+        for (key, value) in plain_environment(project, service, &mut unmapped) {
+            // A key without a value is passed through from compose's own environment
+            environment.insert(key.into(), value.as_deref().map(literal).unwrap_or(Value::Null));
+        }
+        if !environment.is_empty() {
+            spec.insert("environment".into(), Value::Mapping(environment));
+        }
+
+        let env_files: Vec<Value> = project.env_file.iter().chain(&service.env_file).map(|file| file.as_str().into()).collect();
+        if !env_files.is_empty() {
+            spec.insert("env_file".into(), Value::Sequence(env_files));
+        }
+
+        if !service.volumes.is_empty() {
+            let mut volumes = Vec::new();
+            for volume in &service.volumes {
+                volumes.push(literal(&compose_volume(volume, dir)));
+                match volume.split_once(':') {
+                    Some((source, _)) if !source.starts_with(['.', '/', '~']) => {
+                        named_volumes.insert(source.into(), Value::Null);
+                    }
+                    _ => {}
+                }
+            }
+            spec.insert("volumes".into(), Value::Sequence(volumes));
+        }
+
+        let dependencies: Vec<&String> = service.dependencies.iter().filter(|d| names.contains(d.as_str())).collect();
+        if !dependencies.is_empty() {
+            let condition = |dependency: &str| {
+                if jobs.contains(dependency) {
+                    Some("service_completed_successfully")
+                } else if health_checks.contains_key(dependency) {
+                    Some("service_healthy")
+                } else {
+                    None
+                }
+            };
+            // Compose needs the long form as soon as one dependency has a condition
+            let depends_on = if dependencies.iter().any(|d| condition(d).is_some()) {
+                let mut conditions = Mapping::new();
+                for dependency in dependencies {
+                    let mut wait = Mapping::new();
+                    wait.insert("condition".into(), condition(dependency).unwrap_or("service_started").into());
+                    conditions.insert(dependency.as_str().into(), Value::Mapping(wait));
+                }
+                Value::Mapping(conditions)
+            } else {
+                Value::Sequence(dependencies.into_iter().map(|d| d.as_str().into()).collect())
+            };
+            spec.insert("depends_on".into(), depends_on);
+        }
+
+        if let Some(check) = health_checks.get(service.name.as_str()) {
+            spec.insert("healthcheck".into(), Value::Mapping(check.clone()));
+        }
+        if service.replicas > 1 {
+            let mut deploy = Mapping::new();
+            deploy.insert("replicas".into(), service.replicas.into());
+            spec.insert("deploy".into(), Value::Mapping(deploy));
+            if spec.contains_key("ports") {
+                unmapped.push(format!(
+                    "services.{}.replicas: replicas share the published ports in compose instead of offsetting them", service.name
+                ));
+            }
+        }
+        note_devspin_only(service, &mut unmapped);
+
+        compose_services.insert(service.name.as_str().into(), Value::Mapping(spec));
+    }
+
+    let mut compose = Mapping::new();
+    compose.insert("name".into(), project.name.to_lowercase().into());
+    compose.insert("services".into(), Value::Mapping(compose_services));
+    if !named_volumes.is_empty() {
+        compose.insert("volumes".into(), Value::Mapping(named_volumes));
+    }
+
+    let yaml = serde_yaml::to_string(&Value::Mapping(compose))
+        .map_err(|e| ToolError::ParseError(format!("Failed to write compose file: {}", e)))?;
+    Ok(Exported {
+        content: format!("# Generated from the devspin config by `devspin export compose`\n{}", yaml),
+        unmapped,
+    })
+}
+
+/// Runs through `sh -c`, the way devspin runs commands.
+fn shell_command(command: &str) -> Value {
+    Value::Sequence(vec!["sh".into(), "-c".into(), literal(command)])
+}
+
+/// A string compose must pass through as written. Compose interpolates
+/// `$VAR` from the host when it reads the file, so `$` is doubled.
+fn literal(text: &str) -> Value {
+    text.replace('$', "$$").into()
+}
+
+/// Rebases a relative host path from the service directory onto the
+/// project root, which is where compose resolves it.
+fn compose_volume(volume: &str, dir: &str) -> String {
+    match volume.split_once(':') {
+        Some((host, rest)) if host.starts_with('.') && !dir.is_empty() => {
+            let host = host.strip_prefix("./").unwrap_or(host);
+            format!("./{}/{}:{}", dir, host, rest)
+        }
+        _ => volume.to_string(),
+    }
+}
+
+/// The check as run inside the service's own container, where the port is
+/// the container side of the mapping. `None` when that port is unknown.
+fn compose_health_check(service: &Service, check: &HealthCheck) -> Option<Mapping> {
+    let inner_port = |port: u16| match (service.kind, service.port, service.container_port) {
+        (ServiceKind::Container, Some(PortSpec::Fixed(host)), Some(container_port)) if host == port => container_port,
+        _ => port,
+    };
+    let main_port = match service.port {
+        Some(PortSpec::Fixed(port)) => Some(inner_port(port)),
+        _ if service.kind == ServiceKind::Container => service.container_port,
+        _ => None,
+    };
+
+    let test = match check.type_entry.as_str() {
+        "http" => {
+            let vars: HashMap<String, String> = main_port.map(|port| ("PORT".to_string(), port.to_string())).into_iter().collect();
+            let target = check.resolve(&vars).http_target;
+            if target.contains("${") {
+                return None;
+            }
+            vec!["CMD".into(), "curl".into(), "-f".into(), literal(&target)]
+        }
+        "port" => {
+            let port = check.port.map(inner_port).or(main_port)?;
+            vec!["CMD-SHELL".into(), format!("nc -z localhost {}", port).into()]
+        }
+        _ => return None,
+    };
+
+    let mut health_check = Mapping::new();
+    health_check.insert("test".into(), Value::Sequence(test));
+    if let Some(timeout) = check.timeout {
+        health_check.insert("start_period".into(), format!("{}s", timeout).into());
+    }
+    Some(health_check)
+}
+
+/// A scalar as text, the way it would appear in the compose file.
+fn render(value: &Value) -> String {
+    match value {
+        Value::String(text) => text.clone(),
+        Value::Number(number) => number.to_string(),
+        Value::Bool(flag) => flag.to_string(),
+        other => serde_yaml::to_string(other).unwrap_or_default().trim().to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::configs::yaml_parser::{ProjectConfig, ServiceKind};
+
+    const COMPOSE: &str = r#"
+name: shop
+services:
+  db:
+    image: postgres:16
+    environment:
+      POSTGRES_PASSWORD: secret
+      PGDATA:
+    ports: ["5432:5432"]
+    healthcheck:
+      test: ["CMD-SHELL", "pg_isready -U postgres"]
+      interval: 5s
+    volumes: ["./pgdata:/var/lib/postgresql/data"]
+  api:
+    build: .
+    command: ["npm", "run", "start"]
+    environment: ["NODE_ENV=development"]
+    env_file: .env
+    ports: ["3001:3000", "9229:9229"]
+    depends_on:
+      db: { condition: service_healthy }
+    healthcheck:
+      test: curl -f http://localhost:3000/health
+    restart: unless-stopped
+networks:
+  default: {}
+"#;
+
+    #[test]
+    fn test_from_compose() {
+        let compose: Value = serde_yaml::from_str(COMPOSE).unwrap();
+        let imported = from_compose(&compose, "fallback").unwrap();
+        let project: ProjectConfig = serde_yaml::from_value(imported.config).unwrap();
+        assert_eq!(project.name, "shop");
+
+        let services = project.services.unwrap();
+        let db = &services[0];
+        assert_eq!(db.kind, ServiceKind::Container);
+        assert_eq!(db.image.as_deref(), Some("postgres:16"));
+        assert_eq!(db.health_check.as_ref().unwrap().type_entry, "port");
+        assert_eq!(db.volumes, vec!["./pgdata:/var/lib/postgresql/data"]);
+
+        let api = &services[1];
+        assert_eq!(api.image.as_deref(), Some("shop-api"));
+        assert_eq!(api.command, "npm run start");
+        assert_eq!(api.dependencies, vec!["db"]);
+        assert_eq!(api.container_port, Some(3000));
+        assert_eq!(api.health_check.as_ref().unwrap().http_target, "http://localhost:${PORT}/health");
+        assert_eq!(api.env_file, vec![".env"]);
+
+        let unmapped = imported.unmapped.join("\n");
+        assert!(unmapped.contains("networks: not supported"));
+        assert!(unmapped.contains("services.db.environment.PGDATA"));
+        assert!(unmapped.contains("services.api.build"));
+        assert!(unmapped.contains("services.api.restart: not supported"));
+        assert!(!unmapped.contains("depends_on"));
+    }
+
+    #[test]
+    fn test_parse_port() {
+        assert_eq!(parse_port(&Value::from("8080:80")), Some((Some(8080), 80)));
+        assert_eq!(parse_port(&Value::from("127.0.0.1:8080:80/tcp")), Some((Some(8080), 80)));
+        assert_eq!(parse_port(&Value::from(80)), Some((None, 80)));
+        assert_eq!(parse_port(&Value::from("53:53/udp")), None);
+        assert_eq!(parse_port(&Value::from("3000-3005:3000-3005")), None);
+    }
+
+    #[test]
+    fn test_to_compose() {
+        let mut project: ProjectConfig = serde_yaml::from_str(r#"
+name: Shop
+environment: { LOG_LEVEL: debug, TOKEN: { secret: { command: "pass shop/token" } } }
+services:
+  - name: db
+    kind: container
+    image: postgres:16
+    port: 5433
+    container_port: 5432
+    volumes: ["./data:/var/lib/postgresql/data", "pgdata:/backups"]
+    working_dir: db
+    health_check: { type_entry: port, port: 5433, http_target: "" }
+    dependencies: []
+  - name: migrate
+    kind: oneshot
+    command: npm run migrate
+    dependencies: [db]
+  - name: api
+    command: npm run dev -- --port $PORT
+    port: 3001
+    environment: { GREETING: "pa ss$word" }
+    health_check: { type_entry: http, http_target: "http://localhost:${PORT}/health", timeout: 10 }
+    dependencies: [db, migrate, billing]
+  - name: billing
+    kind: external
+    port: 9000
+    health_check: { type_entry: port, http_target: "" }
+    dependencies: []
+"#).unwrap();
+        project.base_path = Some(std::env::temp_dir().join("devspin-no-such-project"));
+
+        let exported = to_compose(&project).unwrap();
+        let compose: Value = serde_yaml::from_str(&exported.content).unwrap();
+        assert_eq!(compose["name"], Value::from("shop"));
+        let db = &compose["services"]["db"];
+        assert_eq!(db["ports"][0], Value::from("5433:5432"));
+        assert_eq!(db["volumes"][0], Value::from("./db/data:/var/lib/postgresql/data"));
+        assert_eq!(db["healthcheck"]["test"][1], Value::from("nc -z localhost 5432"));
+        assert!(compose["volumes"].get("pgdata").is_some());
+
+        let api = &compose["services"]["api"];
+        assert_eq!(api["build"]["context"], Value::from("."));
+        assert_eq!(api["command"][2], Value::from("npm run dev -- --port $$PORT"));
+        assert_eq!(api["environment"]["GREETING"], Value::from("pa ss$$word"));
+        assert_eq!(api["environment"]["PORT"], Value::from("3001"));
+        assert_eq!(api["environment"]["LOG_LEVEL"], Value::from("debug"));
+        assert!(api["environment"]["TOKEN"].is_null());
+        assert_eq!(api["healthcheck"]["test"][3], Value::from("http://localhost:3001/health"));
+        assert_eq!(api["depends_on"]["db"]["condition"], Value::from("service_healthy"));
+        assert_eq!(api["depends_on"]["migrate"]["condition"], Value::from("service_completed_successfully"));
+        assert!(api["depends_on"].get("billing").is_none());
+        assert!(compose["services"].get("billing").is_none());
+
+        let unmapped = exported.unmapped.join("\n");
+        assert!(unmapped.contains("services.billing: external"));
+        assert!(unmapped.contains("environment.TOKEN: secret"));
+        assert!(unmapped.contains("services.api: needs a Dockerfile"));
+    }
+}
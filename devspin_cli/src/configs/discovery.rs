@@ -0,0 +1,126 @@
+use std::path::{Path, PathBuf};
+
+use crate::configs::format::find_config_in;
+use crate::configs::paths::registry_file;
+use crate::configs::registry::ProjectRegistry;
+use crate::error::{Result, ToolError};
+
+/// Finds the config file for a project.
+///
+/// Resolution order:
+/// 1. an explicit `--file` path
+/// 2. `name` as a directory containing a devspin config
+/// 3. `name` as a project registered in the user-level registry
+/// 4. without a name, the nearest config walking up from the current directory
+pub fn locate_project(name: Option<&str>, file: Option<&str>) -> Result<PathBuf> {
+    locate_from(name, file, &std::env::current_dir()?)
+}
+
+/// `locate_project`, walking up from `cwd` instead of the current directory.
+fn locate_from(name: Option<&str>, file: Option<&str>, cwd: &Path) -> Result<PathBuf> {
+    if let Some(file) = file {
+        let path = PathBuf::from(file);
+        if path.is_file() {
+            return Ok(path);
+        }
+        return Err(ToolError::ProjectNotFound(format!("config file {} does not exist", file)));
+    }
+
+    let mut tried = Vec::new();
+
+    match name {
+        Some(name) => {
+            let dir = Path::new(name);
+            if let Some(path) = find_config_in(dir) {
+                return Ok(path);
+            }
+            tried.push(format!("{}/devspin.{{yaml,yml,toml,json}}", dir.display()));
+
+            if let Some(project) = ProjectRegistry::load()?.find(name) {
+                if project.config_path.is_file() {
+                    return Ok(project.config_path.clone());
+                }
+                tried.push(format!("{} (registered, missing)", project.config_path.display()));
+            } else {
+                tried.push(format!("'{}' in {}", name, registry_file().display()));
+            }
+
+            Err(ToolError::ProjectNotFound(format!("'{}' (tried: {})", name, tried.join("; "))))
+        }
+        None => {
+            for dir in cwd.ancestors() {
+                if let Some(path) = find_config_in(dir) {
+                    return Ok(path);
+                }
+                tried.push(dir.display().to_string());
+            }
+
+            Err(ToolError::ProjectNotFound(format!(
+                "no devspin config in the current directory or any parent (tried: {})",
+                tried.join("; ")
+            )))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn project_tree() -> TempDir {
+        let temp_dir = TempDir::new().unwrap();
+        std::fs::create_dir_all(temp_dir.path().join("shop/api/src")).unwrap();
+        std::fs::write(temp_dir.path().join("shop/devspin.yaml"), "name: shop\n").unwrap();
+        temp_dir
+    }
+
+    #[test]
+    fn test_walks_up_from_cwd() {
+        let temp_dir = project_tree();
+        let found = locate_from(None, None, &temp_dir.path().join("shop/api/src")).unwrap();
+        assert_eq!(found, temp_dir.path().join("shop/devspin.yaml"));
+    }
+
+    #[test]
+    fn test_file_bypasses_lookup() {
+        let temp_dir = project_tree();
+        let config = temp_dir.path().join("shop/devspin.yaml");
+        let found = locate_from(Some("elsewhere"), Some(&config.to_string_lossy()), temp_dir.path()).unwrap();
+        assert_eq!(found, config);
+
+        let missing = temp_dir.path().join("shop/missing.yaml");
+        let err = locate_from(None, Some(&missing.to_string_lossy()), temp_dir.path()).unwrap_err();
+        assert!(err.to_string().contains("does not exist"));
+    }
+
+    #[test]
+    fn test_name_as_directory_or_registered_project() {
+        let temp_dir = project_tree();
+        let dir = temp_dir.path().join("shop");
+        let found = locate_from(Some(&dir.to_string_lossy()), None, temp_dir.path()).unwrap();
+        assert_eq!(found, dir.join("devspin.yaml"));
+
+        let _lock = crate::configs::registry::TEST_LOCK.lock().unwrap();
+        let mut registry = ProjectRegistry::load().unwrap();
+        registry.register("discovery-shop", &dir.join("devspin.yaml")).unwrap();
+        registry.save().unwrap();
+        let found = locate_from(Some("discovery-shop"), None, temp_dir.path()).unwrap();
+        assert_eq!(found, dir.join("devspin.yaml").canonicalize().unwrap());
+    }
+
+    #[test]
+    fn test_not_found_lists_paths_tried() {
+        let temp_dir = project_tree();
+        let _lock = crate::configs::registry::TEST_LOCK.lock().unwrap();
+        let err = locate_from(Some("discovery-nowhere"), None, temp_dir.path()).unwrap_err().to_string();
+        assert!(err.contains("discovery-nowhere/devspin.{yaml,yml,toml,json}"));
+        assert!(err.contains(&format!("'discovery-nowhere' in {}", registry_file().display())));
+
+        let empty = temp_dir.path().join("empty");
+        std::fs::create_dir(&empty).unwrap();
+        let err = locate_from(None, None, &empty).unwrap_err().to_string();
+        assert!(err.contains("no devspin config in the current directory or any parent"));
+        assert!(err.contains(&format!("tried: {}; {}", empty.display(), temp_dir.path().display())));
+    }
+}
@@ -0,0 +1,266 @@
+use std::collections::{HashMap, HashSet};
+use std::path::Path;
+
+use crate::configs::secrets::{EnvValue, Redactor};
+use crate::configs::yaml_parser::{ProjectConfig, Service, Task};
+use crate::error::{Result, ToolError};
+
+/// Resolved variables for a child process, remembering which came from secrets.
+#[derive(Debug, Default, Clone)]
+pub struct ResolvedEnv {
+    pub vars: HashMap<String, String>,
+    secret_keys: HashSet<String>,
+}
+
+impl ResolvedEnv {
+    fn set(&mut self, key: &str, value: String, secret: bool) {
+        if secret {
+            self.secret_keys.insert(key.to_string());
+        } else {
+            self.secret_keys.remove(key);
+        }
+        self.vars.insert(key.to_string(), value);
+    }
+
+    fn extend_plain(&mut self, vars: HashMap<String, String>) {
+        for (key, value) in vars {
+            self.set(&key, value, false);
+        }
+    }
+
+    fn extend_config(&mut self, vars: &HashMap<String, EnvValue>, base_path: &Path) -> Result<()> {
+        for (key, value) in vars {
+            self.set(key, value.resolve(key, base_path)?, value.is_secret());
+        }
+        Ok(())
+    }
+
+    /// Sets a variable unless a config or command-line layer already did.
+    pub fn set_default(&mut self, key: &str, value: String) {
+        if !self.vars.contains_key(key) {
+            self.set(key, value, false);
+        }
+    }
+
+    /// Sets a plain variable, replacing any earlier value.
+    pub fn insert(&mut self, key: &str, value: String) {
+        self.set(key, value, false);
+    }
+
+    /// Layers `--set` values over everything else.
+    pub fn apply_overrides(&mut self, overrides: &[(String, String)]) {
+        self.extend_plain(overrides.iter().cloned().collect());
+    }
+
+    pub fn is_secret(&self, key: &str) -> bool {
+        self.secret_keys.contains(key)
+    }
+
+    /// A redactor masking every secret value in this environment.
+    pub fn redactor(&self) -> Redactor {
+        Redactor::new(self.secret_keys.iter().filter_map(|key| self.vars.get(key).cloned()))
+    }
+}
+
+/// Builds the environment handed to a service's child process.
+///
+/// Layers, from lowest to highest precedence:
+/// 1. the OS environment (inherited by the child, not part of the map)
+/// 2. project `env_file`s, then any extra files passed with `--env`
+/// 3. project `environment`
+/// 4. service `env_file`s
+/// 5. service `environment`
+/// 6. `--set KEY=VAL` from the command line
+///
+/// Layers 2 and 3 come from `resolve_project_env`, computed once per start.
+/// Env file paths are relative to the project's base path. Nothing here
+/// touches devspin's own process environment.
+pub fn resolve_service_env(
+    project: &ProjectConfig,
+    service: &Service,
+    project_env: &ResolvedEnv,
+    overrides: &[(String, String)],
+) -> Result<ResolvedEnv> {
+    layer_env(project, project_env, &service.env_file, service.environment.as_ref(), overrides)
+}
+
+/// Same layering as `resolve_service_env`, with a task's `env_file` and
+/// `environment` in place of the service's.
+pub fn resolve_task_env(
+    project: &ProjectConfig,
+    task: &Task,
+    project_env: &ResolvedEnv,
+    overrides: &[(String, String)],
+) -> Result<ResolvedEnv> {
+    layer_env(project, project_env, &task.env_file, task.environment.as_ref(), overrides)
+}
+
+fn layer_env(
+    project: &ProjectConfig,
+    project_env: &ResolvedEnv,
+    env_files: &[String],
+    environment: Option<&HashMap<String, EnvValue>>,
+    overrides: &[(String, String)],
+) -> Result<ResolvedEnv> {
+    let mut env = project_env.clone();
+
+    for env_file in env_files {
+        env.extend_plain(read_env_file(&project.resolve_path(env_file))?);
+    }
+    if let Some(environment) = environment {
+        env.extend_config(environment, &project.resolve_path(""))?;
+    }
+
+    env.apply_overrides(overrides);
+    Ok(env)
+}
+
+/// Project-level layers only (env files, `--env` files, `environment`).
+/// Secrets referenced here are resolved at this point.
+pub fn resolve_project_env(project: &ProjectConfig, extra_env_files: &[String]) -> Result<ResolvedEnv> {
+    let mut env = ResolvedEnv::default();
+
+    for env_file in &project.env_file {
+        env.extend_plain(read_env_file(&project.resolve_path(env_file))?);
+    }
+    for env_file in extra_env_files {
+        env.extend_plain(read_env_file(Path::new(env_file))?);
+    }
+    if let Some(project_env) = &project.environment {
+        env.extend_config(project_env, &project.resolve_path(""))?;
+    }
+
+    Ok(env)
+}
+
+/// Parses a dotenv file without exporting anything into our own environment.
+pub fn read_env_file(path: &Path) -> Result<HashMap<String, String>> {
+    let entries = dotenvy::from_path_iter(path)
+        .map_err(|e| ToolError::ConfigError(format!("Failed to load env file {}: {}", path.display(), e)))?;
+
+    entries
+        .map(|entry| entry.map_err(|e| ToolError::ConfigError(format!(
+            "Invalid entry in env file {}: {}", path.display(), e
+        ))))
+        .collect()
+}
+
+/// Expands `${VAR}` references; unknown variables are left as written.
+pub fn expand_vars(text: &str, vars: &HashMap<String, String>) -> String {
+    let mut result = String::with_capacity(text.len());
+    let mut rest = text;
+
+    while let Some(start) = rest.find("${") {
+        let Some(len) = rest[start..].find('}') else { break };
+        let name = &rest[start + 2..start + len];
+        result.push_str(&rest[..start]);
+        match vars.get(name) {
+            Some(value) => result.push_str(value),
+            None => result.push_str(&rest[start..start + len + 1]),
+        }
+        rest = &rest[start + len + 1..];
+    }
+
+    result.push_str(rest);
+    result
+}
+
+/// Parses a `KEY=VAL` pair from the command line.
+pub fn parse_key_val(input: &str) -> std::result::Result<(String, String), String> {
+    let (key, value) = input
+        .split_once('=')
+        .ok_or_else(|| format!("expected KEY=VAL, got '{}'", input))?;
+
+    if key.trim().is_empty() {
+        return Err(format!("empty variable name in '{}'", input));
+    }
+    Ok((key.trim().to_string(), value.to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_precedence_order() {
+        let dir = TempDir::new().unwrap();
+        std::fs::write(dir.path().join("project.env"), "A=project_file\nB=project_file\nC=project_file\nD=project_file\nE=project_file\n").unwrap();
+        std::fs::write(dir.path().join("service.env"), "C=service_file\nD=service_file\nE=service_file\n").unwrap();
+
+        let config_path = dir.path().join("devspin.yaml");
+        std::fs::write(&config_path, r#"
+name: app
+commands:
+  start:
+    dev: dev
+    build: build
+env_file: project.env
+environment:
+  B: project_env
+  C: project_env
+services:
+  - name: api
+    service_type: api
+    command: run
+    dependencies: []
+    env_file: [service.env]
+    environment:
+      D: service_env
+      E: service_env
+"#).unwrap();
+
+        let project = ProjectConfig::load(&config_path.to_string_lossy(), None).unwrap();
+        let service = &project.services.as_ref().unwrap()[0];
+        let project_env = resolve_project_env(&project, &[]).unwrap();
+        let env = resolve_service_env(&project, service, &project_env, &[("E".to_string(), "cli".to_string())]).unwrap().vars;
+
+        assert_eq!(env["A"], "project_file");
+        assert_eq!(env["B"], "project_env");
+        assert_eq!(env["C"], "service_file");
+        assert_eq!(env["D"], "service_env");
+        assert_eq!(env["E"], "cli");
+    }
+
+    #[test]
+    fn test_secrets_are_tracked_for_redaction() {
+        let dir = TempDir::new().unwrap();
+        std::fs::write(dir.path().join("db.secret"), "s3cret\n").unwrap();
+
+        let config_path = dir.path().join("devspin.yaml");
+        std::fs::write(&config_path, r#"
+name: app
+commands:
+  start:
+    dev: dev
+    build: build
+environment:
+  DB_PASSWORD:
+    secret: { file: db.secret }
+  DB_USER: app
+"#).unwrap();
+
+        let project = ProjectConfig::load(&config_path.to_string_lossy(), None).unwrap();
+        let env = resolve_project_env(&project, &[]).unwrap();
+
+        assert_eq!(env.vars["DB_PASSWORD"], "s3cret");
+        assert!(env.is_secret("DB_PASSWORD"));
+        assert!(!env.is_secret("DB_USER"));
+        assert_eq!(env.redactor().redact("pw=s3cret user=app"), "pw=******** user=app");
+    }
+
+    #[test]
+    fn test_expand_vars() {
+        let vars = HashMap::from([("PORT".to_string(), "4001".to_string())]);
+        assert_eq!(expand_vars("http://localhost:${PORT}/health", &vars), "http://localhost:4001/health");
+        assert_eq!(expand_vars("${MISSING}:${PORT}", &vars), "${MISSING}:4001");
+        assert_eq!(expand_vars("no vars ${", &vars), "no vars ${");
+    }
+
+    #[test]
+    fn test_parse_key_val() {
+        assert_eq!(parse_key_val("A=b=c").unwrap(), ("A".to_string(), "b=c".to_string()));
+        assert!(parse_key_val("novalue").is_err());
+        assert!(parse_key_val("=x").is_err());
+    }
+}
@@ -0,0 +1,87 @@
+use std::collections::{BTreeMap, HashMap};
+
+use crate::configs::secrets::EnvValue;
+use crate::configs::yaml_parser::{PortSpec, ProjectConfig, Service, ServiceKind};
+use crate::process::ports::env_name;
+
+/// A file generated from the project config for another tool, with notes on
+/// everything that could not be carried over.
+#[derive(Debug)]
+pub struct Exported {
+    pub content: String,
+    pub unmapped: Vec<String>,
+}
+
+/// Services another tool can run: disabled services and external ones
+/// (which devspin does not run either) are left out with a note.
+pub fn exported_services<'a>(project: &'a ProjectConfig, unmapped: &mut Vec<String>) -> Vec<&'a Service> {
+    let mut services = Vec::new();
+    for service in project.services.iter().flatten() {
+        if !service.enabled {
+            unmapped.push(format!("services.{}: disabled, left out", service.name));
+        } else if service.kind == ServiceKind::External {
+            unmapped.push(format!("services.{}: external, left out (not run by devspin either)", service.name));
+        } else {
+            services.push(service);
+        }
+    }
+    services
+}
+
+/// The variables a service sees from its config: the project environment
+/// with the service's on top, sorted by name. Secrets are never written
+/// out, so they come back as `None` with a note.
+pub fn plain_environment(project: &ProjectConfig, service: &Service, unmapped: &mut Vec<String>) -> BTreeMap<String, Option<String>> {
+    let mut environment = BTreeMap::new();
+    let mut layer = |prefix: &str, vars: Option<&HashMap<String, EnvValue>>| {
+        for (key, value) in vars.into_iter().flatten() {
+            let value = match value {
+                EnvValue::Plain(value) => Some(value.clone()),
+                EnvValue::Secret { .. } => {
+                    let note = format!("{}environment.{}: secret, left for the environment to provide", prefix, key);
+                    if !unmapped.contains(&note) {
+                        unmapped.push(note);
+                    }
+                    None
+                }
+            };
+            environment.insert(key.clone(), value);
+        }
+    };
+    layer("", project.environment.as_ref());
+    layer(&format!("services.{}.", service.name), service.environment.as_ref());
+    environment
+}
+
+/// `PORT` and `<NAME>_PORT` for the service's fixed ports, as devspin sets
+/// them when it starts the service. Auto ports have no number to write.
+pub fn fixed_port_vars(service: &Service, unmapped: &mut Vec<String>) -> BTreeMap<String, String> {
+    let mut vars = BTreeMap::new();
+    let ports = service.port.iter().map(|port| ("PORT".to_string(), "port".to_string(), port)).chain(
+        service.ports.iter().map(|(name, port)| (format!("{}_PORT", env_name(name)), format!("ports.{}", name), port)),
+    );
+    for (var, key, port) in ports {
+        match port {
+            PortSpec::Fixed(port) => {
+                vars.insert(var, port.to_string());
+            }
+            PortSpec::Auto(_) => unmapped.push(format!(
+                "services.{}.{}: auto ports are picked at start; set a fixed port to export it", service.name, key
+            )),
+        }
+    }
+    vars
+}
+
+/// Notes the service settings no exporter carries over.
+pub fn note_devspin_only(service: &Service, unmapped: &mut Vec<String>) {
+    let mut note = |key: &str, present: bool| {
+        if present {
+            unmapped.push(format!("services.{}.{}: not exported", service.name, key));
+        }
+    };
+    note("setup", service.setup.is_some());
+    note("hooks", service.hooks.is_some());
+    note("watch", service.watch.is_some());
+    note("limits", service.limits.is_some());
+}
@@ -0,0 +1,156 @@
+use std::path::{Path, PathBuf};
+
+use serde::Serialize;
+use serde_yaml::Value;
+
+use crate::error::{Result, ToolError};
+
+/// Config file names looked up in a project directory, in order of preference.
+pub const CONFIG_FILE_NAMES: [&str; 4] = ["devspin.yaml", "devspin.yml", "devspin.toml", "devspin.json"];
+
+/// On-disk formats a project config can be written in. All of them are
+/// parsed into the same YAML value tree, so merging and validation behave
+/// identically whatever the format.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum ConfigFormat {
+    Yaml,
+    Toml,
+    Json,
+}
+
+impl ConfigFormat {
+    /// Detects the format from the file extension.
+    pub fn from_path(path: &Path) -> Result<Self> {
+        match path.extension().and_then(|ext| ext.to_str()) {
+            Some("yaml") | Some("yml") => Ok(ConfigFormat::Yaml),
+            Some("toml") => Ok(ConfigFormat::Toml),
+            Some("json") => Ok(ConfigFormat::Json),
+            _ => Err(ToolError::ConfigError(format!(
+                "Unsupported config format: {} (expected .yaml, .yml, .toml or .json)",
+                path.display()
+            ))),
+        }
+    }
+
+    pub fn extension(&self) -> &'static str {
+        match self {
+            ConfigFormat::Yaml => "yaml",
+            ConfigFormat::Toml => "toml",
+            ConfigFormat::Json => "json",
+        }
+    }
+
+    pub fn parse(&self, content: &str) -> std::result::Result<Value, String> {
+        match self {
+            ConfigFormat::Yaml => serde_yaml::from_str(content).map_err(|e| e.to_string()),
+            ConfigFormat::Toml => toml::from_str(content).map_err(|e| e.to_string()),
+            ConfigFormat::Json => serde_json::from_str(content).map_err(|e| e.to_string()),
+        }
+    }
+
+    pub fn serialize<T: Serialize>(&self, value: &T) -> Result<String> {
+        if *self == ConfigFormat::Toml {
+            // TOML has no null, which overlays use to remove keys
+            let tree = serde_yaml::to_value(value)
+                .map_err(|e| ToolError::ConfigError(e.to_string()))?;
+            if let Some(key) = find_null(&tree, "") {
+                return Err(ToolError::ConfigError(format!(
+                    "Cannot write config as toml: '{}' is null, which TOML cannot express", key
+                )));
+            }
+        }
+
+        let output = match self {
+            ConfigFormat::Yaml => serde_yaml::to_string(value).map_err(|e| e.to_string()),
+            ConfigFormat::Toml => toml::to_string_pretty(value).map_err(|e| e.to_string()),
+            ConfigFormat::Json => serde_json::to_string_pretty(value)
+                .map(|json| json + "\n")
+                .map_err(|e| e.to_string()),
+        };
+
+        output.map_err(|e| ToolError::ConfigError(format!(
+            "Cannot write config as {}: {}", self.extension(), e
+        )))
+    }
+}
+
+fn find_null(value: &Value, key: &str) -> Option<String> {
+    match value {
+        Value::Null => Some(key.to_string()),
+        Value::Mapping(mapping) => mapping.iter().find_map(|(child_key, child)| {
+            let child_key = child_key.as_str().unwrap_or_default();
+            let child_path = if key.is_empty() { child_key.to_string() } else { format!("{}.{}", key, child_key) };
+            find_null(child, &child_path)
+        }),
+        Value::Sequence(items) => items
+            .iter()
+            .enumerate()
+            .find_map(|(index, item)| find_null(item, &format!("{}[{}]", key, index))),
+        _ => None,
+    }
+}
+
+/// Reads a config file (or fragment/overlay) in whatever format its extension says.
+pub fn read_value(path: &Path) -> Result<Value> {
+    let format = ConfigFormat::from_path(path)?;
+    let content = std::fs::read_to_string(path)
+        .map_err(|e| ToolError::ConfigError(format!("Failed to read {}: {}", path.display(), e)))?;
+
+    format
+        .parse(&content)
+        .map_err(|e| ToolError::ParseError(format!("{}: {}", path.display(), e)))
+}
+
+/// The project config in `dir`, if there is one.
+pub fn find_config_in(dir: &Path) -> Option<PathBuf> {
+    CONFIG_FILE_NAMES
+        .iter()
+        .map(|name| dir.join(name))
+        .find(|path| path.is_file())
+}
+
+/// First existing `<dir>/<stem>.<ext>` for any supported extension.
+pub fn find_with_stem(dir: &Path, stem: &str) -> Option<PathBuf> {
+    ["yaml", "yml", "toml", "json"]
+        .iter()
+        .map(|ext| dir.join(format!("{}.{}", stem, ext)))
+        .find(|path| path.is_file())
+}
+
+/// Strips a supported config extension from a file name.
+pub fn strip_extension(file_name: &str) -> Option<&str> {
+    ["yaml", "yml", "toml", "json"]
+        .iter()
+        .find_map(|ext| file_name.strip_suffix(&format!(".{}", ext)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_formats_parse_to_the_same_value() {
+        let yaml = ConfigFormat::Yaml
+            .parse("name: app\nenvironment:\n  PORT: '3000'\nservices:\n  - name: api\n    dependencies: []\n")
+            .unwrap();
+        let toml = ConfigFormat::Toml
+            .parse("name = \"app\"\n\n[environment]\nPORT = \"3000\"\n\n[[services]]\nname = \"api\"\ndependencies = []\n")
+            .unwrap();
+        let json = ConfigFormat::Json
+            .parse(r#"{"name": "app", "environment": {"PORT": "3000"}, "services": [{"name": "api", "dependencies": []}]}"#)
+            .unwrap();
+
+        assert_eq!(yaml, toml);
+        assert_eq!(yaml, json);
+    }
+
+    #[test]
+    fn test_toml_round_trip() {
+        let value = ConfigFormat::Yaml
+            .parse("name: app\ncommands:\n  start:\n    dev: run\nservices:\n  - name: api\n    port: 3000\n")
+            .unwrap();
+        let toml = ConfigFormat::Toml.serialize(&value).unwrap();
+
+        assert_eq!(ConfigFormat::Toml.parse(&toml).unwrap(), value);
+    }
+}
@@ -0,0 +1,71 @@
+use serde_yaml::{Mapping, Value};
+
+use crate::configs::kinds::validate_services;
+use crate::configs::yaml_parser::ProjectConfig;
+use crate::error::{Result, ToolError};
+
+/// A project config converted from another tool's format, with notes on
+/// everything that could not be carried over.
+#[derive(Debug)]
+pub struct Imported {
+    pub config: Value,
+    pub unmapped: Vec<String>,
+}
+
+impl Imported {
+    /// Assembles the config from service mappings and checks that devspin
+    /// would load it.
+    pub fn new(name: &str, mut services: Vec<Mapping>, unmapped: Vec<String>) -> Result<Self> {
+        for service in &mut services {
+            // Required by the config format, even when empty
+            if !service.contains_key("dependencies") {
+                service.insert("dependencies".into(), Value::Sequence(Vec::new()));
+            }
+        }
+
+        let mut config = Mapping::new();
+        config.insert("name".into(), name.into());
+        config.insert("services".into(), Value::Sequence(services.into_iter().map(Value::Mapping).collect()));
+        let config = Value::Mapping(config);
+
+        let project: ProjectConfig = serde_yaml::from_value(config.clone())
+            .map_err(|e| ToolError::ParseError(format!("Converted config is invalid: {}", e)))?;
+        validate_services(&project)?;
+
+        Ok(Imported { config, unmapped })
+    }
+}
+
+/// Starts a service mapping with its name.
+pub fn service_mapping(name: &str) -> Mapping {
+    let mut service = Mapping::new();
+    service.insert("name".into(), name.into());
+    service
+}
+
+/// Joins an argument list into one shell command, quoting where needed.
+pub fn shell_join(args: &[String]) -> String {
+    args.iter()
+        .map(|arg| {
+            let plain = !arg.is_empty() && arg.chars().all(|c| c.is_ascii_alphanumeric() || "-_./:=@%+,".contains(c));
+            if plain {
+                arg.clone()
+            } else {
+                format!("'{}'", arg.replace('\'', r"'\''"))
+            }
+        })
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_shell_join_quotes_where_needed() {
+        let args = ["python".to_string(), "-c".to_string(), "print('hi there')".to_string()];
+        assert_eq!(shell_join(&args), r#"python -c 'print('\''hi there'\'')'"#);
+        assert_eq!(shell_join(&["npm".to_string(), "run".to_string(), "dev".to_string()]), "npm run dev");
+    }
+}
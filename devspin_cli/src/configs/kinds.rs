@@ -0,0 +1,114 @@
+use serde_yaml::Value;
+
+use crate::configs::yaml_parser::{PortSpec, ProjectConfig, Service, ServiceKind};
+use crate::error::{Result, ToolError};
+
+/// Lets older configs that wrote an execution kind into `service_type`
+/// (e.g. `service_type: external`) keep working: it becomes the `kind`
+/// unless one is set. Other labels such as `api` are left alone.
+pub fn normalize_kinds(config: &mut Value) {
+    let Some(Value::Sequence(services)) = config.get_mut("services") else { return };
+
+    for service in services.iter_mut().filter_map(Value::as_mapping_mut) {
+        if service.contains_key("kind") {
+            continue;
+        }
+        let Some(label) = service.get("service_type").and_then(Value::as_str) else { continue };
+        if ServiceKind::ALL.iter().any(|kind| kind.name() == label) {
+            let label = label.to_string();
+            service.insert(Value::from("kind"), Value::from(label));
+        }
+    }
+}
+
+/// Checks that each service has the fields its kind needs and none that
+/// make no sense for it.
+pub fn validate_services(project: &ProjectConfig) -> Result<()> {
+    for service in project.services.iter().flatten() {
+        validate_service(service)?;
+    }
+    Ok(())
+}
+
+fn validate_service(service: &Service) -> Result<()> {
+    let kind = service.kind.name();
+    let invalid = |problem: &str| {
+        Err(ToolError::ValidationError(format!("Service '{}' ({}) {}", service.name, kind, problem)))
+    };
+
+    let container_only = service.image.is_some() || service.container_port.is_some() || !service.volumes.is_empty();
+    if container_only && service.kind != ServiceKind::Container {
+        return invalid("sets an image, container_port or volumes, which only container services use");
+    }
+
+    match service.kind {
+        ServiceKind::Process | ServiceKind::Oneshot if service.command.trim().is_empty() => invalid("needs a command"),
+        ServiceKind::Oneshot if service.replicas != 1 || service.watch.is_some() => {
+            invalid("runs once and cannot have replicas or watch settings")
+        }
+        ServiceKind::Container if service.image.as_deref().is_none_or(|image| image.trim().is_empty()) => {
+            invalid("needs an image")
+        }
+        ServiceKind::Container if service.limits.is_some() || service.watch.is_some() => {
+            invalid("cannot have limits or watch settings")
+        }
+        ServiceKind::External if !service.command.trim().is_empty() => {
+            invalid("is not started by devspin and cannot have a command")
+        }
+        ServiceKind::External if service.health_check.is_none() => invalid("needs a health_check to know when it is up"),
+        ServiceKind::External
+            if service.replicas != 1
+                || service.setup.is_some()
+                || service.limits.is_some()
+                || service.hooks.is_some()
+                || service.watch.is_some() =>
+        {
+            invalid("is not managed by devspin and cannot have replicas, setup, limits, hooks or watch settings")
+        }
+        ServiceKind::External
+            if service.port.iter().chain(service.ports.values()).any(|port| matches!(port, PortSpec::Auto(_))) =>
+        {
+            invalid("must use fixed ports; devspin cannot choose them for it")
+        }
+        _ => Ok(()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn services(yaml: &str) -> Vec<Service> {
+        let mut value: Value = serde_yaml::from_str(yaml).unwrap();
+        normalize_kinds(&mut value);
+        serde_yaml::from_value(value["services"].clone()).unwrap()
+    }
+
+    #[test]
+    fn test_kind_from_legacy_service_type() {
+        let services = services(r#"
+services:
+  - { name: api, service_type: api, command: run, dependencies: [] }
+  - { name: db, service_type: external, dependencies: [], health_check: { type_entry: port, port: 5432, http_target: "" } }
+"#);
+        assert_eq!(services[0].kind, ServiceKind::Process);
+        assert_eq!(services[0].service_type.as_deref(), Some("api"));
+        assert_eq!(services[1].kind, ServiceKind::External);
+        assert!(services.iter().all(|service| validate_service(service).is_ok()));
+    }
+
+    #[test]
+    fn test_kind_specific_validation() {
+        let services = services(r#"
+services:
+  - { name: web, dependencies: [] }
+  - { name: cache, kind: container, dependencies: [] }
+  - { name: db, kind: external, command: postgres, dependencies: [], health_check: { type_entry: port, port: 5432, http_target: "" } }
+  - { name: migrate, kind: oneshot, command: migrate, dependencies: [], replicas: 2 }
+  - { name: api, command: run, image: "node:20", dependencies: [] }
+"#);
+        for service in &services {
+            assert!(matches!(validate_service(service), Err(ToolError::ValidationError(_))), "{}", service.name);
+        }
+    }
+}
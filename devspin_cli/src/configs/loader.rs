@@ -0,0 +1,415 @@
+use std::collections::{BTreeMap, HashMap};
+use std::path::{Path, PathBuf};
+
+use serde_yaml::Value;
+
+use crate::configs::format::{find_with_stem, read_value, strip_extension};
+use crate::configs::merge::{deep_merge, item_name, is_named_list};
+use crate::configs::kinds::{normalize_kinds, validate_services};
+use crate::configs::tasks::normalize_commands;
+use crate::configs::yaml_parser::ProjectConfig;
+use crate::error::{Result, ToolError};
+
+/// Keys a fragment pulled in through `include:` is allowed to contribute.
+const FRAGMENT_KEYS: [&str; 3] = ["services", "environment", "hooks"];
+
+/// Per-developer override merged last; meant to stay out of version control.
+/// This is the YAML name, used in messages; whichever of `devspin.local.yaml`,
+/// `.toml` or `.json` exists is the one merged (see `format::find_with_stem`).
+pub const LOCAL_OVERRIDE_FILE: &str = "devspin.local.yaml";
+
+/// `.gitignore` pattern covering the local override in every format.
+pub const LOCAL_OVERRIDE_PATTERN: &str = "devspin.local.*";
+
+/// The merged config before it is turned into a `ProjectConfig`, along with
+/// the file every value came from.
+#[derive(Debug)]
+pub struct ResolvedConfig {
+    pub value: Value,
+    /// Value path (e.g. `services[api].command`) -> file that set it
+    pub origins: BTreeMap<String, String>,
+    /// Every file that contributed, in merge order
+    pub sources: Vec<PathBuf>,
+}
+
+impl ResolvedConfig {
+    fn new() -> Self {
+        ResolvedConfig {
+            value: Value::Mapping(Default::default()),
+            origins: BTreeMap::new(),
+            sources: Vec::new(),
+        }
+    }
+
+    /// Merges `layer` over the current value and records where its values came from.
+    fn apply(&mut self, layer: Value, path: &Path, label: String) {
+        record_origins(&layer, "", &label, &mut self.origins);
+        deep_merge(&mut self.value, layer);
+        if !self.sources.iter().any(|source| source == path) {
+            self.sources.push(path.to_path_buf());
+        }
+    }
+
+    /// The file that set the value at `key`, falling back to its closest parent.
+    pub fn origin_of(&self, key: &str) -> Option<&str> {
+        let mut key = key;
+        loop {
+            if let Some(origin) = self.origins.get(key) {
+                return Some(origin);
+            }
+            let parent = key.rfind(['.', '['])?;
+            key = &key[..parent];
+        }
+    }
+}
+
+/// Loads a project config, applying the given profile on top of it.
+///
+/// Merge order, each layer deep-merged over the previous one (see
+/// `configs::merge`):
+/// 1. files listed under `include:`
+/// 2. the main config file
+/// 3. the `profiles.<profile>` section of the main file
+/// 4. a `devspin.<profile>.yaml` overlay next to it
+/// 5. `devspin.local.yaml` (or `.toml`/`.json`) next to it, when present
+///
+/// Any of these files may be YAML, TOML or JSON (see `configs::format`).
+pub fn load_project(path: &Path, profile: Option<&str>) -> Result<ProjectConfig> {
+    let mut resolved = resolve_project(path, profile)?;
+    normalize_commands(&mut resolved.value);
+    normalize_kinds(&mut resolved.value);
+    normalize_environment(&mut resolved.value);
+
+    let mut config: ProjectConfig = serde_yaml::from_value(resolved.value)
+        .map_err(|e| ToolError::ParseError(format!("{}: {}", path.display(), e)))?;
+    validate_services(&config)?;
+
+    config.base_path = Some(
+        path.parent()
+            .map(|p| p.to_path_buf())
+            .unwrap_or_else(|| PathBuf::from(".")),
+    );
+    config.active_profile = profile.map(str::to_string);
+    config.sources = resolved.sources;
+    config.config_path = Some(path.to_path_buf());
+
+    Ok(config)
+}
+
+/// Builds the merged config value for `path` without deserializing it.
+pub fn resolve_project(path: &Path, profile: Option<&str>) -> Result<ResolvedConfig> {
+    let dir = path.parent().unwrap_or_else(|| Path::new("."));
+    let main = read_value(path)?;
+    let main_label = file_label(path);
+    let mut resolved = ResolvedConfig::new();
+
+    if let Some(includes) = main.get("include") {
+        let includes: Vec<String> = serde_yaml::from_value(includes.clone())
+            .map_err(|e| ToolError::ParseError(format!("{}: invalid include list: {}", path.display(), e)))?;
+        apply_includes(&mut resolved, dir, &includes)?;
+    }
+
+    let profile_layers = match profile {
+        Some(profile) => profile_layers(&main, path, profile)?,
+        None => Vec::new(),
+    };
+
+    resolved.apply(main, path, main_label);
+    for (layer_path, label, layer) in profile_layers {
+        resolved.apply(layer, &layer_path, label);
+    }
+
+    if let Some(local_path) = find_with_stem(dir, "devspin.local") {
+        let mut local = read_value(&local_path)?;
+        strip_profiles(&mut local);
+        resolved.apply(local, &local_path, file_label(&local_path));
+    }
+
+    Ok(resolved)
+}
+
+/// Turns number and bool values under every `environment:` into strings,
+/// so `PORT: 3000` reads the same as `PORT: "3000"`.
+fn normalize_environment(config: &mut Value) {
+    stringify_scalars(config.get_mut("environment"));
+    if let Some(Value::Sequence(services)) = config.get_mut("services") {
+        for service in services {
+            stringify_scalars(service.get_mut("environment"));
+        }
+    }
+    if let Some(Value::Mapping(commands)) = config.get_mut("commands") {
+        for (_, task) in commands.iter_mut() {
+            stringify_scalars(task.get_mut("environment"));
+        }
+    }
+}
+
+fn stringify_scalars(environment: Option<&mut Value>) {
+    let Some(Value::Mapping(vars)) = environment else { return };
+    for (_, value) in vars.iter_mut() {
+        let text = match &*value {
+            Value::Number(number) => number.to_string(),
+            Value::Bool(flag) => flag.to_string(),
+            _ => continue,
+        };
+        *value = Value::from(text);
+    }
+}
+
+fn file_label(path: &Path) -> String {
+    path.file_name()
+        .map(|name| name.to_string_lossy().to_string())
+        .unwrap_or_else(|| path.display().to_string())
+}
+
+fn apply_includes(resolved: &mut ResolvedConfig, dir: &Path, includes: &[String]) -> Result<()> {
+    let mut service_owners: HashMap<String, PathBuf> = HashMap::new();
+    let mut hook_owners: HashMap<String, PathBuf> = HashMap::new();
+
+    for include in includes {
+        let fragment_path = dir.join(include);
+        let fragment = read_value(&fragment_path)?;
+        let Some(mapping) = fragment.as_mapping() else {
+            return Err(ToolError::ValidationError(format!(
+                "{}: an included file must be a mapping", fragment_path.display()
+            )));
+        };
+
+        for (key, value) in mapping {
+            let key_name = key.as_str().unwrap_or_default();
+            match key_name {
+                "services" => {
+                    for service in value.as_sequence().map(Vec::as_slice).unwrap_or_default() {
+                        let name = item_name(service).ok_or_else(|| ToolError::ValidationError(format!(
+                            "{}: every included service needs a name", fragment_path.display()
+                        )))?;
+                        claim(&mut service_owners, "Service", name, &fragment_path)?;
+                    }
+                }
+                "hooks" => {
+                    for hook in value.as_mapping().into_iter().flat_map(|m| m.keys()) {
+                        claim(&mut hook_owners, "Hook", hook.as_str().unwrap_or_default(), &fragment_path)?;
+                    }
+                }
+                "environment" => {}
+                _ => {
+                    return Err(ToolError::ValidationError(format!(
+                        "{}: '{}' is not allowed in an included file (expected one of: {})",
+                        fragment_path.display(), key_name, FRAGMENT_KEYS.join(", ")
+                    )));
+                }
+            }
+        }
+
+        // Conflicts are ruled out above, so a plain merge only adds to what is there
+        resolved.apply(fragment, &fragment_path, include.clone());
+    }
+
+    Ok(())
+}
+
+/// Records that `owner` defines `name`, failing if another fragment already did.
+fn claim(owners: &mut HashMap<String, PathBuf>, what: &str, name: &str, owner: &Path) -> Result<()> {
+    if let Some(previous) = owners.get(name) {
+        return Err(ToolError::ValidationError(format!(
+            "{} '{}' is defined in both {} and {}",
+            what, name, previous.display(), owner.display()
+        )));
+    }
+    owners.insert(name.to_string(), owner.to_path_buf());
+    Ok(())
+}
+
+/// The layers a profile adds: the inline `profiles.<name>` section, then the overlay file.
+fn profile_layers(main: &Value, path: &Path, profile: &str) -> Result<Vec<(PathBuf, String, Value)>> {
+    if profile == "local" {
+        return Err(ToolError::ConfigError(format!(
+            "'local' is reserved for {}, which is always applied", LOCAL_OVERRIDE_FILE
+        )));
+    }
+
+    let mut layers = Vec::new();
+
+    if let Some(inline) = main.get("profiles").and_then(|profiles| profiles.get(profile)) {
+        layers.push((path.to_path_buf(), format!("{} (profile {})", file_label(path), profile), inline.clone()));
+    }
+
+    let overlay_path = profile_overlay_path(path, profile);
+    if overlay_path.exists() {
+        layers.push((overlay_path.clone(), file_label(&overlay_path), read_value(&overlay_path)?));
+    }
+
+    if layers.is_empty() {
+        let available = available_profiles(main, path);
+        return Err(ToolError::ConfigError(format!(
+            "Profile '{}' not found (available: {})",
+            profile,
+            if available.is_empty() { "none".to_string() } else { available.join(", ") }
+        )));
+    }
+
+    for (_, _, layer) in &mut layers {
+        strip_profiles(layer);
+    }
+
+    Ok(layers)
+}
+
+fn strip_profiles(layer: &mut Value) {
+    if let Some(mapping) = layer.as_mapping_mut() {
+        mapping.remove("profiles");
+    }
+}
+
+/// Path of the `devspin.<profile>.<ext>` overlay that sits next to `path`.
+/// Falls back to the `.yaml` name when none exists yet.
+pub fn profile_overlay_path(path: &Path, profile: &str) -> PathBuf {
+    let dir = path.parent().unwrap_or_else(|| Path::new("."));
+    let stem = format!("devspin.{}", profile);
+    find_with_stem(dir, &stem).unwrap_or_else(|| dir.join(format!("{}.yaml", stem)))
+}
+
+/// Profile names declared inline or as overlay files next to `path`.
+pub fn available_profiles(value: &Value, path: &Path) -> Vec<String> {
+    let mut profiles: Vec<String> = value
+        .get("profiles")
+        .and_then(Value::as_mapping)
+        .map(|mapping| {
+            mapping
+                .keys()
+                .filter_map(|key| key.as_str().map(str::to_string))
+                .collect()
+        })
+        .unwrap_or_default();
+
+    let dir = path.parent().unwrap_or_else(|| Path::new("."));
+    if let Ok(entries) = std::fs::read_dir(dir) {
+        for entry in entries.flatten() {
+            let file_name = entry.file_name().to_string_lossy().to_string();
+            if let Some(profile) = file_name
+                .strip_prefix("devspin.")
+                .and_then(strip_extension)
+            {
+                if profile != "local" && !profiles.iter().any(|p| p == profile) {
+                    profiles.push(profile.to_string());
+                }
+            }
+        }
+    }
+
+    profiles.sort();
+    profiles
+}
+
+/// Walks the leaves of `value`, recording `label` as their origin.
+///
+/// Items of name-keyed lists are addressed as `services[api]`; any other
+/// list is a single leaf since merging replaces it as a whole.
+fn record_origins(value: &Value, key: &str, label: &str, origins: &mut BTreeMap<String, String>) {
+    match value {
+        Value::Mapping(mapping) if !mapping.is_empty() => {
+            for (child_key, child) in mapping {
+                let Some(child_key) = child_key.as_str() else { continue };
+                let child_path = if key.is_empty() {
+                    child_key.to_string()
+                } else {
+                    format!("{}.{}", key, child_key)
+                };
+                record_origins(child, &child_path, label, origins);
+            }
+        }
+        Value::Sequence(items) if !items.is_empty() && is_named_list(items) => {
+            for item in items {
+                let item_path = format!("{}[{}]", key, item_name(item).unwrap_or_default());
+                record_origins(item, &item_path, label, origins);
+            }
+        }
+        _ => {
+            origins.insert(key.to_string(), label.to_string());
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::configs::secrets::EnvValue;
+    use tempfile::TempDir;
+
+    const BASE: &str = "name: app\ncommands:\n  start:\n    dev: dev\n    build: build\n";
+
+    fn write(dir: &TempDir, file: &str, content: &str) -> PathBuf {
+        let path = dir.path().join(file);
+        std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+        std::fs::write(&path, content).unwrap();
+        path
+    }
+
+    #[test]
+    fn test_profile_overlay_file_is_applied() {
+        let dir = TempDir::new().unwrap();
+        let path = write(&dir, "devspin.yaml", &format!("{}hooks:\n  pre_start: echo hi\n", BASE));
+        write(&dir, "devspin.ci.yaml", "hooks: ~\n");
+
+        let config = load_project(&path, Some("ci")).unwrap();
+        assert!(config.hooks.is_none());
+        assert_eq!(config.active_profile.as_deref(), Some("ci"));
+
+        assert!(load_project(&path, Some("missing")).is_err());
+    }
+
+    #[test]
+    fn test_number_and_bool_environment_values() {
+        let dir = TempDir::new().unwrap();
+        let path = write(&dir, "devspin.yaml", &format!(
+            "{}environment: {{ PORT: 3000, DEBUG: true }}\nservices:\n  - {{ name: api, command: run, dependencies: [], environment: {{ WORKERS: 4, RATIO: 0.5 }} }}\n",
+            BASE
+        ));
+
+        let config = load_project(&path, None).unwrap();
+        let environment = config.environment.unwrap();
+        assert_eq!(environment["PORT"], EnvValue::Plain("3000".to_string()));
+        assert_eq!(environment["DEBUG"], EnvValue::Plain("true".to_string()));
+        let service_env = config.services.unwrap()[0].environment.clone().unwrap();
+        assert_eq!(service_env["WORKERS"], EnvValue::Plain("4".to_string()));
+        assert_eq!(service_env["RATIO"], EnvValue::Plain("0.5".to_string()));
+    }
+
+    #[test]
+    fn test_includes_contribute_services() {
+        let dir = TempDir::new().unwrap();
+        write(&dir, "api/devspin.yaml", "services:\n  - name: api\n    service_type: api\n    command: run api\n    dependencies: []\n");
+        let path = write(&dir, "devspin.yaml", &format!("{}include: [api/devspin.yaml]\n", BASE));
+
+        let config = load_project(&path, None).unwrap();
+        let services = config.services.unwrap();
+        assert_eq!(services.len(), 1);
+        assert_eq!(services[0].command, "run api");
+    }
+
+    #[test]
+    fn test_duplicate_included_service_is_rejected() {
+        let dir = TempDir::new().unwrap();
+        let fragment = "services:\n  - name: api\n    service_type: api\n    command: run\n    dependencies: []\n";
+        write(&dir, "a.yaml", fragment);
+        write(&dir, "b.yaml", fragment);
+        let path = write(&dir, "devspin.yaml", &format!("{}include: [a.yaml, b.yaml]\n", BASE));
+
+        let err = load_project(&path, None).unwrap_err().to_string();
+        assert!(err.contains("'api' is defined in both"), "{}", err);
+    }
+
+    #[test]
+    fn test_local_override_is_merged_and_tracked() {
+        let dir = TempDir::new().unwrap();
+        let path = write(&dir, "devspin.yaml", &format!("{}environment:\n  PORT: '3000'\n", BASE));
+        write(&dir, LOCAL_OVERRIDE_FILE, "environment:\n  PORT: '4000'\n");
+
+        let resolved = resolve_project(&path, None).unwrap();
+        assert_eq!(resolved.origin_of("environment.PORT"), Some(LOCAL_OVERRIDE_FILE));
+        assert_eq!(resolved.origin_of("commands.start.dev"), Some("devspin.yaml"));
+
+        let config = load_project(&path, None).unwrap();
+        assert_eq!(config.environment.unwrap()["PORT"].to_string(), "4000");
+    }
+}
@@ -0,0 +1,114 @@
+use serde_yaml::{Mapping, Value};
+
+/// Deep-merges `overlay` on top of `base`.
+///
+/// Merge rules:
+/// - mappings are merged key by key, recursively
+/// - an explicit `null` in the overlay removes the key from the result
+/// - lists whose items are all mappings with a `name` key (e.g. `services`)
+///   are merged item by item on `name`; unknown names are appended
+/// - anything else (scalars, other lists) is replaced by the overlay value
+pub fn deep_merge(base: &mut Value, overlay: Value) {
+    match (base, overlay) {
+        (Value::Mapping(base_map), Value::Mapping(overlay_map)) => {
+            merge_mappings(base_map, overlay_map);
+        }
+        (Value::Sequence(base_seq), Value::Sequence(overlay_seq))
+            if is_named_list(base_seq) && is_named_list(&overlay_seq) =>
+        {
+            merge_named_lists(base_seq, overlay_seq);
+        }
+        (base, overlay) => {
+            *base = overlay;
+        }
+    }
+}
+
+fn merge_mappings(base: &mut Mapping, overlay: Mapping) {
+    for (key, value) in overlay {
+        if value.is_null() {
+            base.remove(&key);
+            continue;
+        }
+
+        match base.get_mut(&key) {
+            Some(existing) => deep_merge(existing, value),
+            None => {
+                base.insert(key, value);
+            }
+        }
+    }
+}
+
+fn merge_named_lists(base: &mut Vec<Value>, overlay: Vec<Value>) {
+    for item in overlay {
+        let name = item_name(&item).map(str::to_string);
+        let existing = base
+            .iter_mut()
+            .find(|candidate| item_name(candidate).map(str::to_string) == name);
+
+        match existing {
+            Some(existing) => deep_merge(existing, item),
+            None => base.push(item),
+        }
+    }
+}
+
+pub(crate) fn is_named_list(items: &[Value]) -> bool {
+    items.iter().all(|item| item_name(item).is_some())
+}
+
+pub(crate) fn item_name(item: &Value) -> Option<&str> {
+    item.as_mapping()?.get("name")?.as_str()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn yaml(content: &str) -> Value {
+        serde_yaml::from_str(content).unwrap()
+    }
+
+    #[test]
+    fn test_scalars_are_replaced_and_maps_merged() {
+        let mut base = yaml("name: app\nenvironment:\n  A: '1'\n  B: '2'\n");
+        deep_merge(&mut base, yaml("environment:\n  B: '3'\n  C: '4'\n"));
+
+        assert_eq!(base, yaml("name: app\nenvironment:\n  A: '1'\n  B: '3'\n  C: '4'\n"));
+    }
+
+    #[test]
+    fn test_null_removes_key() {
+        let mut base = yaml("name: app\nhooks:\n  pre_start: echo hi\n");
+        deep_merge(&mut base, yaml("hooks: ~\n"));
+
+        assert_eq!(base, yaml("name: app\n"));
+    }
+
+    #[test]
+    fn test_named_lists_merge_by_name() {
+        let mut base = yaml(
+            "services:\n  - name: api\n    command: run api\n  - name: web\n    command: run web\n",
+        );
+        deep_merge(
+            &mut base,
+            yaml("services:\n  - name: web\n    enabled: false\n  - name: worker\n    command: run worker\n"),
+        );
+
+        assert_eq!(
+            base,
+            yaml(
+                "services:\n  - name: api\n    command: run api\n  - name: web\n    command: run web\n    enabled: false\n  - name: worker\n    command: run worker\n"
+            )
+        );
+    }
+
+    #[test]
+    fn test_plain_lists_are_replaced() {
+        let mut base = yaml("dependencies: [a, b]\n");
+        deep_merge(&mut base, yaml("dependencies: [c]\n"));
+
+        assert_eq!(base, yaml("dependencies: [c]\n"));
+    }
+}
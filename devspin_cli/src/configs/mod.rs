@@ -1 +1,16 @@
-pub mod yaml_parser;
\ No newline at end of file
+pub mod yaml_parser;
+pub mod merge;
+pub mod loader;
+pub mod format;
+pub mod paths;
+pub mod registry;
+pub mod discovery;
+pub mod env;
+pub mod secrets;
+pub mod tasks;
+pub mod kinds;
+pub mod import;
+pub mod compose;
+pub mod export;
+pub mod procfile;
+pub mod systemd;
@@ -0,0 +1,69 @@
+use std::path::PathBuf;
+#[cfg(test)]
+use std::sync::OnceLock;
+
+/// Root directory for devspin's own files (project registry, run state).
+///
+/// `DEVSPIN_HOME` overrides the platform config directory, which keeps
+/// tests and throwaway setups away from the real registry.
+#[cfg(not(test))]
+pub fn devspin_home() -> PathBuf {
+    if let Some(home) = std::env::var_os("DEVSPIN_HOME") {
+        return PathBuf::from(home);
+    }
+
+    dirs::config_dir()
+        .map(|dir| dir.join("devspin"))
+        .unwrap_or_else(|| PathBuf::from(".devspin"))
+}
+
+/// Unit tests share one temporary home, created on first use, so they never
+/// read or write the real registry and run state.
+#[cfg(test)]
+pub fn devspin_home() -> PathBuf {
+    static HOME: OnceLock<tempfile::TempDir> = OnceLock::new();
+    HOME.get_or_init(|| tempfile::Builder::new().prefix("devspin-home").tempdir().unwrap())
+        .path()
+        .to_path_buf()
+}
+
+/// File holding the registry of known projects.
+pub fn registry_file() -> PathBuf {
+    devspin_home().join("projects.json")
+}
+
+/// Directory holding one run record per started project.
+pub fn runs_dir() -> PathBuf {
+    devspin_home().join("runs")
+}
+
+/// Directory holding task input/output fingerprints, one file per project.
+pub fn fingerprints_dir() -> PathBuf {
+    devspin_home().join("fingerprints")
+}
+
+/// Directory holding each project's port assignments.
+pub fn ports_dir() -> PathBuf {
+    devspin_home().join("ports")
+}
+
+/// Directory holding per-replica service logs.
+pub fn logs_dir() -> PathBuf {
+    devspin_home().join("logs")
+}
+
+/// A project or service name made safe to use as a single file name under
+/// the devspin home: anything but letters, digits, `-`, `_` and `.` becomes
+/// `-`, so a name like `../shop` cannot leave its directory.
+pub fn file_stem(name: &str) -> String {
+    let stem: String = name
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() || c == '.' || c == '-' || c == '_' { c } else { '-' })
+        .collect();
+    // `.` and `..` are the only names still pointing outside
+    if stem.chars().all(|c| c == '.') {
+        stem.replace('.', "-")
+    } else {
+        stem
+    }
+}
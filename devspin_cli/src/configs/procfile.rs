@@ -0,0 +1,155 @@
+use serde_yaml::Value;
+
+use crate::configs::export::{exported_services, note_devspin_only, Exported};
+use crate::configs::import::{service_mapping, shell_join, Imported};
+use crate::configs::yaml_parser::{PortSpec, ProjectConfig, ServiceKind};
+use crate::error::{Result, ToolError};
+
+/// Heroku's process type for a command run once before the others start.
+const RELEASE: &str = "release";
+
+/// Converts a Procfile into a devspin config. Every process becomes a
+/// service; a command that reads `$PORT` gets an auto port, as a Procfile
+/// runner would assign one, and `release` becomes a job the rest wait for.
+pub fn from_procfile(text: &str, name: &str, env_file: Option<&str>) -> Result<Imported> {
+    let mut unmapped = Vec::new();
+    let mut processes: Vec<(String, String)> = Vec::new();
+
+    for (number, line) in text.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let parsed = line.split_once(':').map(|(name, command)| (name.trim(), command.trim())).filter(|(name, command)| {
+            !name.is_empty() && !command.is_empty() && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+        });
+        match parsed {
+            Some((name, _)) if processes.iter().any(|(existing, _)| existing == name) => {
+                unmapped.push(format!("line {}: {} is defined twice; the first definition is kept", number + 1, name));
+            }
+            Some((name, command)) => processes.push((name.to_string(), command.to_string())),
+            None => unmapped.push(format!("line {}: not a `name: command` entry", number + 1)),
+        }
+    }
+    if processes.is_empty() {
+        return Err(ToolError::ConfigError("Procfile defines no processes".to_string()));
+    }
+
+    let has_release = processes.iter().any(|(name, _)| name == RELEASE);
+    let services = processes
+        .iter()
+        .map(|(process, command)| {
+            let mut service = service_mapping(process);
+            if process == RELEASE {
+                service.insert("kind".into(), "oneshot".into());
+            }
+            service.insert("command".into(), command.as_str().into());
+            if process != RELEASE && (command.contains("$PORT") || command.contains("${PORT}")) {
+                service.insert("port".into(), "auto".into());
+            }
+            if has_release && process != RELEASE {
+                service.insert("dependencies".into(), vec![RELEASE].into());
+            }
+            service
+        })
+        .collect();
+
+    let mut imported = Imported::new(name, services, unmapped)?;
+    if let (Some(env_file), Value::Mapping(config)) = (env_file, &mut imported.config) {
+        config.insert("env_file".into(), Value::Sequence(vec![env_file.into()]));
+    }
+    Ok(imported)
+}
+
+/// Converts a project config into a Procfile: one line per process service,
+/// run from its working directory. Environment belongs in `.env`, where
+/// Procfile runners read it.
+pub fn to_procfile(project: &ProjectConfig) -> Exported {
+    let mut unmapped = Vec::new();
+    if project.environment.is_some() {
+        unmapped.push("environment: not exported; Procfile runners read .env".to_string());
+    }
+    let services = exported_services(project, &mut unmapped);
+
+    let mut content = String::from("# Generated from the devspin config by `devspin export procfile`\n");
+    for service in services {
+        match service.kind {
+            ServiceKind::Container => {
+                unmapped.push(format!("services.{}: containers have no command to run, left out", service.name));
+                continue;
+            }
+            ServiceKind::Oneshot if service.name != RELEASE => {
+                unmapped.push(format!("services.{}: Procfile processes are long-running; only a job named release is kept", service.name));
+                continue;
+            }
+            _ => {}
+        }
+
+        let mut note = |key: &str, problem: &str| unmapped.push(format!("services.{}.{}: {}", service.name, key, problem));
+        if service.environment.is_some() {
+            note("environment", "not exported; Procfile runners read .env");
+        }
+        // Auto ports lose nothing: the runner picks one the same way
+        if let Some(PortSpec::Fixed(port)) = service.port {
+            note("port", &format!("Procfile runners assign PORT themselves, so {} is not kept", port));
+        }
+        if !service.dependencies.is_empty() {
+            note("dependencies", "Procfile processes all start together");
+        }
+        if service.health_check.is_some() {
+            note("health_check", "not exported");
+        }
+        if service.replicas > 1 {
+            note("replicas", "set the runner's concurrency instead (e.g. foreman start -m web=2)");
+        }
+        note_devspin_only(service, &mut unmapped);
+
+        let command = match service.working_dir.as_deref().filter(|dir| !dir.is_empty()) {
+            Some(dir) => format!("cd {} && {}", shell_join(&[dir.to_string()]), service.command),
+            None => service.command.clone(),
+        };
+        // A Procfile entry has to fit on one line
+        let command = command.lines().map(str::trim).filter(|line| !line.is_empty()).collect::<Vec<_>>().join(" && ");
+        content.push_str(&format!("{}: {}\n", service.name, command));
+    }
+
+    Exported { content, unmapped }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_from_procfile() {
+        let procfile = "# dev processes\nweb: bundle exec rails server -p $PORT\nworker: bundle exec sidekiq\nrelease: bin/rails db:migrate\nnot a process\n";
+        let imported = from_procfile(procfile, "shop", Some(".env")).unwrap();
+        let project: ProjectConfig = serde_yaml::from_value(imported.config).unwrap();
+        assert_eq!(project.env_file, vec![".env"]);
+
+        let services = project.services.unwrap();
+        assert_eq!(services[0].name, "web");
+        assert_eq!(services[0].port.map(|port| port.to_string()), Some("auto".to_string()));
+        assert_eq!(services[0].dependencies, vec!["release"]);
+        assert!(services[1].port.is_none());
+        assert_eq!(services[2].kind, ServiceKind::Oneshot);
+        assert!(services[2].dependencies.is_empty());
+        assert_eq!(imported.unmapped, vec!["line 5: not a `name: command` entry"]);
+    }
+
+    #[test]
+    fn test_to_procfile() {
+        let project: ProjectConfig = serde_yaml::from_str(r#"
+name: shop
+services:
+  - { name: web, command: "npm start", working_dir: web, port: 3000, dependencies: [] }
+  - { name: docs, command: "npm run docs", working_dir: "my docs", dependencies: [] }
+  - { name: cache, kind: container, image: "redis:7", dependencies: [] }
+  - { name: seed, kind: oneshot, command: "npm run seed", dependencies: [] }
+"#).unwrap();
+        let exported = to_procfile(&project);
+        assert!(exported.content.contains("\nweb: cd web && npm start\n"));
+        assert!(exported.content.ends_with("\ndocs: cd 'my docs' && npm run docs\n"));
+        assert_eq!(exported.unmapped.len(), 3);
+    }
+}
@@ -0,0 +1,123 @@
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+
+use crate::configs::paths::registry_file;
+use crate::error::{Result, ToolError};
+
+/// Held by tests that read or write the shared test registry.
+#[cfg(test)]
+pub static TEST_LOCK: std::sync::Mutex<()> = std::sync::Mutex::new(());
+
+/// User-level list of known projects, keyed by project name.
+#[derive(Debug, Default, Deserialize, Serialize)]
+pub struct ProjectRegistry {
+    pub projects: BTreeMap<String, RegisteredProject>,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct RegisteredProject {
+    /// Absolute path of the project's config file
+    pub config_path: PathBuf,
+}
+
+impl ProjectRegistry {
+    /// Loads the registry, treating a missing file as empty.
+    pub fn load() -> Result<Self> {
+        Self::load_from(&registry_file())
+    }
+
+    pub fn load_from(path: &Path) -> Result<Self> {
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+
+        let content = std::fs::read_to_string(path)?;
+        serde_json::from_str(&content).map_err(|e| ToolError::ParseError(format!(
+            "Invalid project registry {}: {}", path.display(), e
+        )))
+    }
+
+    pub fn save(&self) -> Result<()> {
+        let path = registry_file();
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+
+        let content = serde_json::to_string_pretty(self)
+            .map_err(|e| ToolError::GenericError(e.to_string()))?;
+        std::fs::write(path, content)?;
+        Ok(())
+    }
+
+    pub fn find(&self, name: &str) -> Option<&RegisteredProject> {
+        self.projects.get(name)
+    }
+
+    /// Adds or updates a project; the config path is stored absolute.
+    pub fn register(&mut self, name: &str, config_path: &Path) -> Result<()> {
+        let config_path = config_path.canonicalize().map_err(|e| ToolError::ConfigError(format!(
+            "Cannot register {}: {}", config_path.display(), e
+        )))?;
+
+        self.projects.insert(name.to_string(), RegisteredProject { config_path });
+        Ok(())
+    }
+
+    /// Registers a project unless it is already known, saving if anything changed.
+    /// Returns whether the project was newly added, and fails when the name
+    /// is registered for a different config.
+    pub fn ensure_registered(name: &str, config_path: &Path) -> Result<bool> {
+        let mut registry = Self::load()?;
+        if let Some(existing) = registry.find(name) {
+            if config_path.canonicalize().ok().as_ref() != Some(&existing.config_path) {
+                return Err(ToolError::ConfigError(format!(
+                    "a project named '{}' is already registered at {} (use `devspin register --force` to replace it)",
+                    name,
+                    existing.config_path.display()
+                )));
+            }
+            return Ok(false);
+        }
+
+        registry.register(name, config_path)?;
+        registry.save()?;
+        Ok(true)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_registry_round_trip() {
+        let _lock = TEST_LOCK.lock().unwrap();
+        let temp_dir = TempDir::new().unwrap();
+        let config = temp_dir.path().join("devspin.yaml");
+        std::fs::write(&config, "name: registry-shop\n").unwrap();
+
+        assert!(ProjectRegistry::ensure_registered("registry-shop", &config).unwrap());
+        assert!(!ProjectRegistry::ensure_registered("registry-shop", &config).unwrap());
+
+        // Same name, different project
+        let other = temp_dir.path().join("other/devspin.yaml");
+        std::fs::create_dir_all(other.parent().unwrap()).unwrap();
+        std::fs::write(&other, "name: registry-shop\n").unwrap();
+        let err = ProjectRegistry::ensure_registered("registry-shop", &other).unwrap_err();
+        assert!(err.to_string().contains("already registered at"));
+
+        let registry = ProjectRegistry::load_from(&registry_file()).unwrap();
+        let project = registry.find("registry-shop").unwrap();
+        assert_eq!(project.config_path, config.canonicalize().unwrap());
+        assert!(project.config_path.is_absolute());
+
+        let mut registry = ProjectRegistry::load().unwrap();
+        assert!(registry.register("registry-missing", &temp_dir.path().join("missing.yaml")).is_err());
+        registry.projects.remove("registry-shop");
+        registry.save().unwrap();
+        assert!(ProjectRegistry::load().unwrap().find("registry-shop").is_none());
+    }
+}
@@ -0,0 +1,165 @@
+use std::fmt;
+use std::path::{Path, PathBuf};
+
+use serde::de::Error as _;
+use serde::{Deserialize, Deserializer, Serialize};
+
+use crate::error::{Result, ToolError};
+
+/// Replacement shown wherever a secret value would otherwise be printed.
+pub const MASK: &str = "********";
+
+/// Value of an environment variable in the config: either a literal or a
+/// reference to a secret that is only resolved when a service starts.
+///
+/// ```yaml
+/// environment:
+///   DB_USER: app
+///   DB_PASSWORD:
+///     secret: { file: ~/.secrets/db }
+///   API_TOKEN:
+///     secret: { command: "pass show api" }
+/// ```
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq)]
+#[serde(untagged)]
+pub enum EnvValue {
+    Plain(#[serde(deserialize_with = "scalar_string")] String),
+    Secret { secret: SecretSource },
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum SecretSource {
+    /// Read from a file (`~` and paths relative to the config are allowed)
+    File(String),
+    /// Taken from the stdout of a shell command
+    Command(String),
+}
+
+impl EnvValue {
+    pub fn is_secret(&self) -> bool {
+        matches!(self, EnvValue::Secret { .. })
+    }
+
+    /// Produces the actual value, running the secret lookup if needed.
+    pub fn resolve(&self, key: &str, base_path: &Path) -> Result<String> {
+        match self {
+            EnvValue::Plain(value) => Ok(value.clone()),
+            EnvValue::Secret { secret } => secret.resolve(base_path).map_err(|e| {
+                ToolError::ConfigError(format!("Failed to resolve secret {}: {}", key, e))
+            }),
+        }
+    }
+}
+
+/// Prints literals as-is and secrets as their reference, never their value.
+impl fmt::Display for EnvValue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            EnvValue::Plain(value) => write!(f, "{}", value),
+            EnvValue::Secret { secret: SecretSource::File(path) } => write!(f, "{} (secret from file {})", MASK, path),
+            EnvValue::Secret { secret: SecretSource::Command(_) } => write!(f, "{} (secret from command)", MASK),
+        }
+    }
+}
+
+/// Any scalar is a literal: `PORT: 3000` and `DEBUG: true` read as text.
+fn scalar_string<'de, D: Deserializer<'de>>(deserializer: D) -> std::result::Result<String, D::Error> {
+    match serde_yaml::Value::deserialize(deserializer)? {
+        serde_yaml::Value::String(text) => Ok(text),
+        serde_yaml::Value::Number(number) => Ok(number.to_string()),
+        serde_yaml::Value::Bool(flag) => Ok(flag.to_string()),
+        _ => Err(D::Error::custom("expected a string, number or bool")),
+    }
+}
+
+impl SecretSource {
+    fn resolve(&self, base_path: &Path) -> std::result::Result<String, String> {
+        match self {
+            SecretSource::File(path) => {
+                let path = expand_path(path, base_path);
+                std::fs::read_to_string(&path)
+                    .map(|content| content.trim_end_matches(['\n', '\r']).to_string())
+                    .map_err(|e| format!("cannot read {}: {}", path.display(), e))
+            }
+            SecretSource::Command(command) => {
+                let output = std::process::Command::new("sh")
+                    .arg("-c")
+                    .arg(command)
+                    .current_dir(base_path)
+                    .stdin(std::process::Stdio::inherit())
+                    .stderr(std::process::Stdio::inherit())
+                    .output()
+                    .map_err(|e| format!("cannot run secret command: {}", e))?;
+
+                if !output.status.success() {
+                    return Err(format!("secret command exited with {}", output.status));
+                }
+
+                let value = String::from_utf8_lossy(&output.stdout);
+                Ok(value.trim_end_matches(['\n', '\r']).to_string())
+            }
+        }
+    }
+}
+
+fn expand_path(path: &str, base_path: &Path) -> PathBuf {
+    if let Some(rest) = path.strip_prefix("~/") {
+        if let Some(home) = dirs::home_dir() {
+            return home.join(rest);
+        }
+    }
+    base_path.join(path)
+}
+
+/// Masks known secret values in text before it is printed or logged.
+#[derive(Debug, Default, Clone)]
+pub struct Redactor {
+    secrets: Vec<String>,
+}
+
+impl Redactor {
+    pub fn new<I: IntoIterator<Item = String>>(secrets: I) -> Self {
+        let mut secrets: Vec<String> = secrets.into_iter().filter(|s| !s.is_empty()).collect();
+        // Longest first so a secret containing another is masked whole
+        secrets.sort_by_key(|s| std::cmp::Reverse(s.len()));
+        Redactor { secrets }
+    }
+
+    pub fn redact(&self, text: &str) -> String {
+        self.secrets
+            .iter()
+            .fold(text.to_string(), |text, secret| text.replace(secret.as_str(), MASK))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_env_value_forms() {
+        let values: std::collections::HashMap<String, EnvValue> = serde_yaml::from_str(
+            "PLAIN: value\nPORT: 3000\nDEBUG: true\nFROM_FILE:\n  secret: { file: db.txt }\nFROM_CMD:\n  secret: { command: cat db.txt }\n",
+        )
+        .unwrap();
+        let dir = TempDir::new().unwrap();
+        std::fs::write(dir.path().join("db.txt"), "hunter2\n").unwrap();
+
+        assert_eq!(values["PLAIN"], EnvValue::Plain("value".to_string()));
+        assert_eq!(values["PORT"], EnvValue::Plain("3000".to_string()));
+        assert_eq!(values["DEBUG"], EnvValue::Plain("true".to_string()));
+        assert!(values["FROM_FILE"].is_secret());
+        assert_eq!(values["FROM_FILE"].resolve("FROM_FILE", dir.path()).unwrap(), "hunter2");
+        assert_eq!(values["FROM_CMD"].resolve("FROM_CMD", dir.path()).unwrap(), "hunter2");
+        assert!(values["FROM_FILE"].to_string().starts_with(MASK));
+    }
+
+    #[test]
+    fn test_redactor_masks_all_occurrences() {
+        let redactor = Redactor::new(vec!["hunter2".to_string(), String::new()]);
+        assert_eq!(redactor.redact("psql -p hunter2 && echo hunter2"), format!("psql -p {} && echo {}", MASK, MASK));
+        assert_eq!(redactor.redact("nothing here"), "nothing here");
+    }
+}
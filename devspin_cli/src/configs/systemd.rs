@@ -0,0 +1,278 @@
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+use crate::configs::env::{read_env_file, ResolvedEnv};
+use crate::configs::export::{exported_services, fixed_port_vars, plain_environment};
+use crate::configs::yaml_parser::{HookFailure, Limits, ProjectConfig, ServiceKind};
+use crate::process::containers::container_args;
+
+/// One unit file, named as systemd will know it.
+#[derive(Debug)]
+pub struct Unit {
+    pub name: String,
+    pub content: String,
+}
+
+/// Units for a project: one per service plus a target that starts and stops
+/// them together, with notes on everything that could not be carried over.
+#[derive(Debug)]
+pub struct Units {
+    pub target: String,
+    pub units: Vec<Unit>,
+    pub unmapped: Vec<String>,
+}
+
+/// Converts a project config into systemd user units. Dependencies become
+/// `Requires=` and `After=`, jobs become `Type=oneshot` units that stay
+/// active once they succeed, and long-running services restart on failure.
+/// systemd needs absolute paths, so a relative config location is resolved
+/// against `cwd`.
+pub fn to_systemd(project: &ProjectConfig, cwd: &Path) -> Units {
+    let mut unmapped = Vec::new();
+    if project.hooks.is_some() {
+        unmapped.push("hooks: project hooks are not exported (service hooks are)".to_string());
+    }
+    // `-f devspin.yaml` leaves an empty base path
+    let base: PathBuf = cwd.join(project.base_path.as_deref().unwrap_or(Path::new(""))).components().collect();
+    let prefix = format!("devspin-{}", unit_safe(&project.name));
+    let target = format!("{}.target", prefix);
+    let services = exported_services(project, &mut unmapped);
+    let unit_name = |service: &str| format!("{}-{}.service", prefix, unit_safe(service));
+
+    let mut units = Vec::new();
+    for service in &services {
+        let mut note = |key: &str, problem: &str| unmapped.push(format!("services.{}.{}: {}", service.name, key, problem));
+        if service.health_check.is_some() {
+            note("health_check", "not exported; dependents wait only for the unit to start");
+        }
+        if service.replicas > 1 {
+            note("replicas", "not exported; the unit runs one instance");
+        }
+        if service.setup.is_some() {
+            note("setup", "not exported; run `devspin setup` before starting the units");
+        }
+        if service.watch.is_some() {
+            note("watch", "not exported");
+        }
+
+        let dependencies: Vec<String> = service
+            .dependencies
+            .iter()
+            .filter(|dependency| services.iter().any(|s| &&s.name == dependency))
+            .map(|dependency| unit_name(dependency))
+            .collect();
+
+        let mut unit = String::from("# Generated from the devspin config by `devspin export systemd`\n");
+        unit.push_str("[Unit]\n");
+        unit.push_str(&format!("Description={} {} (devspin)\n", project.name, service.name));
+        unit.push_str(&format!("PartOf={}\n", target));
+        if !dependencies.is_empty() {
+            unit.push_str(&format!("Requires={}\n", dependencies.join(" ")));
+            unit.push_str(&format!("After={}\n", dependencies.join(" ")));
+        }
+
+        unit.push_str("\n[Service]\n");
+        if service.kind == ServiceKind::Oneshot {
+            // Stays active after exiting 0, so dependents started after it keep their Requires=
+            unit.push_str("Type=oneshot\nRemainAfterExit=yes\n");
+        } else {
+            unit.push_str("Type=simple\nRestart=on-failure\nRestartSec=2\n");
+        }
+        let working_dir = match service.working_dir.as_deref().filter(|dir| !dir.is_empty()) {
+            Some(dir) => base.join(dir),
+            None => base.clone(),
+        };
+        unit.push_str(&format!("WorkingDirectory={}\n", escape_specifiers(&working_dir.to_string_lossy())));
+
+        let mut environment = BTreeMap::new();
+        for (key, value) in fixed_port_vars(service, &mut unmapped) {
+            environment.insert(key, Some(value));
+        }
+        environment.extend(plain_environment(project, service, &mut unmapped));
+        for (key, value) in &environment {
+            if let Some(value) = value {
+                unit.push_str(&format!("Environment={}\n", quote(&format!("{}={}", key, value))));
+            }
+        }
+        let env_files: Vec<PathBuf> = project.env_file.iter().chain(&service.env_file).map(|file| base.join(file)).collect();
+        for file in &env_files {
+            unit.push_str(&format!("EnvironmentFile={}\n", escape_specifiers(&file.to_string_lossy())));
+        }
+
+        if let Some(limits) = &service.limits {
+            unit.push_str(&limit_lines(limits));
+        }
+
+        let hooks = service.hooks.as_ref();
+        // A `-` prefix lets the unit carry on when the command fails
+        let hook_prefix = match hooks.map(|hooks| hooks.on_failure) {
+            Some(HookFailure::Warn) => "-",
+            _ => "",
+        };
+        let hook = |key: &str, command: Option<&String>| {
+            command.map(|command| format!("{}={}{}\n", key, hook_prefix, shell(command))).unwrap_or_default()
+        };
+
+        unit.push_str(&hook("ExecStartPre", hooks.and_then(|h| h.pre_start.as_ref())));
+        if service.kind == ServiceKind::Container {
+            let name = format!("{}-{}", prefix, unit_safe(&service.name));
+            let mut env = ResolvedEnv::default();
+            for (key, value) in &environment {
+                env.insert(key, value.clone().unwrap_or_default());
+            }
+            // Variables from env files only need their names on the command line
+            for file in &env_files {
+                for key in read_env_file(file).map(|vars| vars.into_keys().collect()).unwrap_or_else(|_| Vec::new()) {
+                    env.insert(&key, String::new());
+                }
+            }
+            let mut args = vec!["docker".to_string(), "run".to_string(), "--rm".to_string()];
+            args.extend(container_args(&project.name, service, 0, &env, &working_dir));
+            // The unit's own name, not devspin's, so both can run side by side
+            if let Some(position) = args.iter().position(|arg| arg == "--name") {
+                args[position + 1] = name.clone();
+            }
+
+            unit.push_str(&format!("ExecStartPre=-docker rm --force {}\n", name));
+            unit.push_str(&format!("ExecStart={}\n", args.iter().map(|arg| exec_quote(arg)).collect::<Vec<_>>().join(" ")));
+            unit.push_str(&hook("ExecStop", hooks.and_then(|h| h.pre_stop.as_ref())));
+            unit.push_str(&format!("ExecStop=docker stop {}\n", name));
+        } else {
+            unit.push_str(&format!("ExecStart={}\n", shell(&service.command)));
+            unit.push_str(&hook("ExecStop", hooks.and_then(|h| h.pre_stop.as_ref())));
+        }
+        unit.push_str(&hook("ExecStartPost", hooks.and_then(|h| h.post_start.as_ref())));
+        unit.push_str(&hook("ExecStopPost", hooks.and_then(|h| h.post_stop.as_ref())));
+
+        units.push(Unit { name: unit_name(&service.name), content: unit });
+    }
+
+    let mut content = String::from("# Generated from the devspin config by `devspin export systemd`\n");
+    content.push_str(&format!("[Unit]\nDescription={} (devspin)\n", project.name));
+    if !units.is_empty() {
+        content.push_str(&format!("Wants={}\n", units.iter().map(|unit| unit.name.as_str()).collect::<Vec<_>>().join(" ")));
+    }
+    content.push_str("\n[Install]\nWantedBy=default.target\n");
+    units.push(Unit { name: target.clone(), content });
+
+    Units { target, units, unmapped }
+}
+
+/// The same `setrlimit` limits devspin applies, as systemd sets them.
+fn limit_lines(limits: &Limits) -> String {
+    let settings = [
+        ("LimitNOFILE", limits.open_files.map(|n| n.to_string())),
+        ("LimitAS", limits.memory.map(|bytes| bytes.to_string())),
+        // devspin sends SIGXCPU at the limit and SIGKILL a second later
+        ("LimitCPU", limits.cpu_seconds.map(|seconds| format!("{}:{}", seconds, seconds.saturating_add(1)))),
+        ("LimitCORE", (limits.core_dumps == Some(false)).then(|| "0".to_string())),
+        ("LimitNPROC", limits.processes.map(|n| n.to_string())),
+    ];
+    settings
+        .into_iter()
+        .filter_map(|(key, value)| Some(format!("{}={}\n", key, value?)))
+        .collect()
+}
+
+/// Runs through `sh -c`, the way devspin runs commands.
+fn shell(command: &str) -> String {
+    format!("/bin/sh -c {}", exec_quote(command))
+}
+
+/// One double-quoted word for a unit file. `%` is doubled so systemd
+/// passes it through instead of expanding a specifier.
+fn quote(word: &str) -> String {
+    let escaped = word.replace('\\', r"\\").replace('"', "\\\"");
+    format!("\"{}\"", escape_specifiers(&escaped))
+}
+
+/// [`quote`] for `Exec*=` lines, where systemd also expands `$VAR`.
+fn exec_quote(word: &str) -> String {
+    quote(&word.replace('$', "$$"))
+}
+
+fn escape_specifiers(text: &str) -> String {
+    text.replace('%', "%%")
+}
+
+/// Keeps a name within the characters systemd allows in unit names.
+fn unit_safe(name: &str) -> String {
+    name.chars().map(|c| if c.is_ascii_alphanumeric() || c == '-' || c == '_' || c == '.' { c } else { '-' }).collect()
+}
+
+/// Where `systemctl --user` looks for units: `$XDG_CONFIG_HOME/systemd/user`.
+pub fn user_unit_dir() -> Option<PathBuf> {
+    dirs::config_dir().map(|dir| dir.join("systemd").join("user"))
+}
+
+/// Units that already exist in `dir`.
+pub fn existing_units<'a>(units: &'a Units, dir: &Path) -> Vec<&'a str> {
+    units.units.iter().filter(|unit| dir.join(&unit.name).exists()).map(|unit| unit.name.as_str()).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_to_systemd() {
+        let mut project: ProjectConfig = serde_yaml::from_str(r#"
+name: My Shop
+environment: { GREETING: "50% off $today" }
+services:
+  - name: migrate
+    kind: oneshot
+    command: ./migrate.sh
+    dependencies: []
+  - name: api
+    command: npm run dev -- --port $PORT
+    working_dir: api
+    port: 3001
+    limits: { open_files: 4096, core_dumps: false }
+    hooks: { pre_start: "echo starting", on_failure: warn }
+    dependencies: [migrate, billing]
+  - name: billing
+    kind: external
+    port: 9000
+    health_check: { type_entry: port, http_target: "" }
+    dependencies: []
+"#).unwrap();
+        project.base_path = Some(PathBuf::from("/srv/shop"));
+
+        let units = to_systemd(&project, Path::new("/home/dev"));
+        assert_eq!(units.target, "devspin-My-Shop.target");
+        let names: Vec<&str> = units.units.iter().map(|unit| unit.name.as_str()).collect();
+        assert_eq!(names, ["devspin-My-Shop-migrate.service", "devspin-My-Shop-api.service", "devspin-My-Shop.target"]);
+
+        assert!(units.units[0].content.contains("Type=oneshot\nRemainAfterExit=yes\n"));
+        let api = &units.units[1].content;
+        assert!(api.contains("Requires=devspin-My-Shop-migrate.service\nAfter=devspin-My-Shop-migrate.service\n"));
+        assert!(api.contains("Restart=on-failure"));
+        assert!(api.contains("WorkingDirectory=/srv/shop/api\n"));
+        assert!(api.contains("Environment=\"GREETING=50%% off $today\"\nEnvironment=\"PORT=3001\"\n"));
+        assert!(api.contains("LimitNOFILE=4096\nLimitCORE=0\n"));
+        assert!(api.contains("ExecStartPre=-/bin/sh -c \"echo starting\"\n"));
+        assert!(api.contains("ExecStart=/bin/sh -c \"npm run dev -- --port $$PORT\"\n"));
+        assert!(units.units[2].content.contains("Wants=devspin-My-Shop-migrate.service devspin-My-Shop-api.service\n"));
+        assert!(units.unmapped.iter().any(|note| note.starts_with("services.billing: external")));
+    }
+
+    #[test]
+    fn test_relative_config_gets_absolute_directories() {
+        let mut project: ProjectConfig = serde_yaml::from_str(r#"
+name: shop
+env_file: [.env]
+services:
+  - { name: api, command: npm run dev, working_dir: api, dependencies: [] }
+  - { name: worker, command: npm run worker, dependencies: [] }
+"#).unwrap();
+
+        for (base_path, root) in [("", "/home/dev/shop"), ("./", "/home/dev/shop"), ("web", "/home/dev/shop/web")] {
+            project.base_path = Some(PathBuf::from(base_path));
+            let units = to_systemd(&project, Path::new("/home/dev/shop"));
+            assert!(units.units[0].content.contains(&format!("WorkingDirectory={}/api\n", root)), "{:?}", base_path);
+            assert!(units.units[1].content.contains(&format!("WorkingDirectory={}\n", root)), "{:?}", base_path);
+            assert!(units.units[1].content.contains(&format!("EnvironmentFile={}/.env\n", root)), "{:?}", base_path);
+        }
+    }
+}
@@ -0,0 +1,135 @@
+use std::collections::{BTreeMap, HashSet};
+
+use serde_yaml::{Mapping, Value};
+
+use crate::configs::yaml_parser::Task;
+use crate::error::{Result, ToolError};
+
+/// Keys that mark a mapping under `commands` as a task rather than the
+/// legacy `commands.start` group.
+const TASK_KEYS: [&str; 8] = [
+    "command", "description", "working_dir", "environment", "env_file", "depends_on", "inputs", "outputs",
+];
+
+/// Rewrites `commands` into the task map `ProjectConfig` expects.
+///
+/// - `build: "cargo build"` becomes `build: { command: "cargo build" }`
+/// - the old `commands: { start: { dev: ..., build: ... } }` layout is
+///   flattened so `dev`, `build`, `test` and `clean` become tasks; a task
+///   defined next to it with the same name wins
+pub fn normalize_commands(config: &mut Value) {
+    let Some(Value::Mapping(commands)) = config.get_mut("commands") else { return };
+
+    let legacy_key = Value::from("start");
+    if let Some(Value::Mapping(start)) = commands.get(&legacy_key) {
+        if !TASK_KEYS.iter().any(|key| start.contains_key(*key)) {
+            let start = start.clone();
+            commands.remove(&legacy_key);
+            for (name, command) in start {
+                if command.is_string() && !commands.contains_key(&name) {
+                    commands.insert(name, command);
+                }
+            }
+        }
+    }
+
+    for (_, task) in commands.iter_mut() {
+        if let Value::String(command) = task {
+            let mut mapping = Mapping::new();
+            mapping.insert(Value::from("command"), Value::from(command.as_str()));
+            *task = Value::Mapping(mapping);
+        }
+    }
+}
+
+/// The tasks to run for `target`, dependencies first, each once.
+pub fn task_order<'a>(tasks: &'a BTreeMap<String, Task>, target: &'a str) -> Result<Vec<&'a str>> {
+    let mut order = Vec::new();
+    let mut done = HashSet::new();
+    let mut path = Vec::new();
+    visit(tasks, target, &mut done, &mut path, &mut order)?;
+    Ok(order)
+}
+
+fn visit<'a>(
+    tasks: &'a BTreeMap<String, Task>,
+    name: &'a str,
+    done: &mut HashSet<&'a str>,
+    path: &mut Vec<&'a str>,
+    order: &mut Vec<&'a str>,
+) -> Result<()> {
+    if done.contains(name) {
+        return Ok(());
+    }
+    if path.contains(&name) {
+        path.push(name);
+        return Err(ToolError::ValidationError(format!(
+            "Task dependency cycle: {}", path.join(" → ")
+        )));
+    }
+
+    let (name, task) = tasks.get_key_value(name).ok_or_else(|| {
+        let known: Vec<&str> = tasks.keys().map(String::as_str).collect();
+        ToolError::ConfigError(format!(
+            "Unknown task '{}' (available: {})",
+            name,
+            if known.is_empty() { "none".to_string() } else { known.join(", ") }
+        ))
+    })?;
+
+    path.push(name);
+    for dependency in &task.depends_on {
+        visit(tasks, dependency, done, path, order)?;
+    }
+    path.pop();
+
+    done.insert(name);
+    order.push(name);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn tasks(yaml: &str) -> BTreeMap<String, Task> {
+        let mut value: Value = serde_yaml::from_str(yaml).unwrap();
+        normalize_commands(&mut value);
+        serde_yaml::from_value(value["commands"].clone()).unwrap()
+    }
+
+    #[test]
+    fn test_legacy_start_block_is_flattened() {
+        let tasks = tasks("commands:\n  start:\n    dev: npm run dev\n    build: npm run build\n  build: make\n  lint:\n    command: eslint .\n");
+
+        assert_eq!(tasks["dev"].command.as_deref(), Some("npm run dev"));
+        assert_eq!(tasks["build"].command.as_deref(), Some("make"));
+        assert_eq!(tasks["lint"].command.as_deref(), Some("eslint ."));
+        assert!(!tasks.contains_key("start"));
+    }
+
+    #[test]
+    fn test_fixtures_load_both_layouts() {
+        let fixture = |name: &str| {
+            let path = format!("{}/tests/fixtures/{}/devspin.yaml", env!("CARGO_MANIFEST_DIR"), name);
+            crate::configs::yaml_parser::ProjectConfig::load(&path, None).unwrap().commands
+        };
+
+        let legacy = fixture("verbose-project");
+        assert_eq!(legacy.keys().collect::<Vec<_>>(), ["build", "clean", "dev", "test"]);
+        assert_eq!(legacy["test"].command.as_deref(), Some("npm test --verbose"));
+
+        let tasks = fixture("task-project");
+        assert_eq!(task_order(&tasks, "test").unwrap(), vec!["build", "test"]);
+        assert_eq!(tasks["clean"].description.as_deref(), Some("Remove dependencies and build output"));
+    }
+
+    #[test]
+    fn test_task_order_and_cycles() {
+        let tasks = tasks("commands:\n  build: make\n  test:\n    command: make test\n    depends_on: [build]\n  ci:\n    depends_on: [test, build]\n  a:\n    depends_on: [b]\n  b:\n    depends_on: [a]\n");
+
+        assert_eq!(task_order(&tasks, "ci").unwrap(), vec!["build", "test", "ci"]);
+        assert!(task_order(&tasks, "a").is_err());
+        assert!(task_order(&tasks, "missing").is_err());
+    }
+}
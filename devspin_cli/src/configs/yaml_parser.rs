@@ -1,80 +1,417 @@
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 use std::path::PathBuf;
 
+use crate::configs::secrets::EnvValue;
+
 #[derive(Debug, Deserialize, Serialize, Clone)]
 pub struct ProjectConfig {
     pub name: String,
     pub description: Option<String>,
-    pub commands: Commands,
+    /// Tasks by name (build, test, clean or anything custom)
+    #[serde(default)]
+    pub commands: BTreeMap<String, Task>,
     pub services: Option<Vec<Service>>,
-    pub environment: Option<HashMap<String, String>>,
+    pub environment: Option<HashMap<String, EnvValue>>,
+
+    /// Dotenv files loaded before `environment` (relative to the config)
+    #[serde(default, deserialize_with = "one_or_many", skip_serializing_if = "Vec::is_empty")]
+    pub env_file: Vec<String>,
+
     pub hooks: Option<Hooks>,
 
+    /// Extra YAML files (relative to the config) contributing services,
+    /// environment and hooks
+    pub include: Option<Vec<String>>,
+
+    /// Named overlays merged on top of this config with `--profile`
+    pub profiles: Option<HashMap<String, serde_yaml::Value>>,
+
     #[serde(skip)]  // Don't serialize/deserialize this from YAML
     pub base_path: Option<PathBuf>,
-}
 
-#[derive(Debug, Deserialize, Serialize, Clone)]
-pub struct Commands {
-    pub start: StartCommands
-    // add other later
-}
+    #[serde(skip)]
+    pub active_profile: Option<String>,
 
-#[derive(Debug, Deserialize, Serialize, Clone)]
-pub struct StartCommands {
-    pub dev: String,
-    pub test: Option<String>,
-    pub build: String,
-    pub clean: Option<String>,
+    /// The main config file this project was loaded from
+    #[serde(skip)]
+    pub config_path: Option<PathBuf>,
 
-    pub services: Option<Services>
+    /// Files merged into this config, in merge order
+    #[serde(skip)]
+    pub sources: Vec<PathBuf>,
 }
 
-#[derive(Debug, Deserialize, Serialize, Clone)]
-pub struct Services {
-    pub services: Vec<Service>
+/// A named command run with `devspin run <task>`.
+///
+/// In the config a task is either a plain command string or a mapping;
+/// the string form is normalized by `configs::tasks` before parsing.
+#[derive(Debug, Deserialize, Serialize, Clone, Default)]
+pub struct Task {
+    /// Shell command; may be omitted for tasks that only group `depends_on`
+    pub command: Option<String>,
+    pub description: Option<String>,
+    /// Relative to the project directory
+    pub working_dir: Option<String>,
+
+    /// Variables for this task only, layered over the project environment
+    pub environment: Option<HashMap<String, EnvValue>>,
+
+    #[serde(default, deserialize_with = "one_or_many", skip_serializing_if = "Vec::is_empty")]
+    pub env_file: Vec<String>,
+
+    /// Tasks that must succeed before this one runs
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub depends_on: Vec<String>,
+
+    /// Globs (relative to the task's directory) whose contents decide whether
+    /// the task must rerun; without them the task always runs
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub inputs: Vec<String>,
+
+    /// Globs for the files the task produces; rerun if they change or vanish
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub outputs: Vec<String>,
 }
 
 #[derive(Debug, Deserialize, Serialize, Clone)]
 pub struct Service {
     pub name: String,
-    pub service_type: String,
+
+    /// How devspin runs the service (defaults to a long-running process)
+    #[serde(default)]
+    pub kind: ServiceKind,
+
+    /// Free-form label shown in output, such as "api" or "database"
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub service_type: Option<String>,
+
+    /// Shell command; required for processes and jobs, optional for containers
+    #[serde(default)]
     pub command: String,
+
+    /// Image to run (containers only)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub image: Option<String>,
+
+    /// Port inside the container that the main `port` is published to
+    /// (containers only; defaults to the same number)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub container_port: Option<u16>,
+
+    /// Volumes as `host:container[:ro]`, relative host paths resolved
+    /// against the service directory (containers only)
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub volumes: Vec<String>,
+
     pub working_dir: Option<String>,
     pub health_check: Option<HealthCheck>,
-    pub dependencies: Vec<String>
+    pub dependencies: Vec<String>,
+
+    /// Variables for this service only, layered over the project environment
+    #[serde(alias = "env")]
+    pub environment: Option<HashMap<String, EnvValue>>,
+
+    /// Dotenv files for this service only (relative to the config)
+    #[serde(default, deserialize_with = "one_or_many", skip_serializing_if = "Vec::is_empty")]
+    pub env_file: Vec<String>,
+
+    /// Set to false (e.g. from a profile) to leave the service out
+    #[serde(default = "default_enabled")]
+    pub enabled: bool,
+
+    /// Hooks run around this service only, inside the project hooks
+    pub hooks: Option<Hooks>,
+
+    /// One-time setup (e.g. `npm install`), rerun only when watched files change
+    #[serde(alias = "setup_command")]
+    pub setup: Option<Setup>,
+
+    /// Files that trigger a restart (or a task) under `devspin start --watch`
+    pub watch: Option<WatchConfig>,
+
+    /// Main port, exposed as `PORT` to the service and `<SERVICE>_PORT` to all
+    pub port: Option<PortSpec>,
+
+    /// Extra named ports, exposed as `<NAME>_PORT` to the service and
+    /// `<SERVICE>_<NAME>_PORT` to all
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub ports: BTreeMap<String, PortSpec>,
+
+    /// Instances to run; each gets `DEVSPIN_REPLICA` and its ports offset by its index
+    #[serde(default = "default_replicas")]
+    pub replicas: u32,
+
+    /// Resource limits applied to the service's processes with `setrlimit`
+    pub limits: Option<Limits>,
+}
+
+/// What kind of thing a service is, which decides how it is started,
+/// waited on and stopped.
+#[derive(Debug, Deserialize, Serialize, Clone, Copy, Default, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum ServiceKind {
+    /// Long-running command, restarted and stopped by devspin
+    #[default]
+    Process,
+    /// Job that runs to completion, such as a migration or seed
+    Oneshot,
+    /// Container started from `image`
+    Container,
+    /// Already running elsewhere; devspin only health-checks it
+    External,
+}
+
+impl ServiceKind {
+    pub const ALL: [ServiceKind; 4] = [ServiceKind::Process, ServiceKind::Oneshot, ServiceKind::Container, ServiceKind::External];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            ServiceKind::Process => "process",
+            ServiceKind::Oneshot => "oneshot",
+            ServiceKind::Container => "container",
+            ServiceKind::External => "external",
+        }
+    }
 }
 
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct WatchConfig {
+    /// Paths or globs relative to the service directory (default: everything)
+    #[serde(default, deserialize_with = "one_or_many", skip_serializing_if = "Vec::is_empty")]
+    pub paths: Vec<String>,
+
+    /// Globs to leave out, on top of `.gitignore`
+    #[serde(default, deserialize_with = "one_or_many", skip_serializing_if = "Vec::is_empty")]
+    pub ignore: Vec<String>,
+
+    /// Quiet period after the last change before acting
+    #[serde(default = "default_debounce_ms")]
+    pub debounce_ms: u64,
+
+    #[serde(default)]
+    pub action: WatchAction,
+
+    /// Task to run when `action` is `run-task`
+    pub task: Option<String>,
+
+    /// Also restart services that depend on this one
+    #[serde(default)]
+    pub restart_dependents: bool,
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone, Copy, Default, PartialEq, Eq)]
+#[serde(rename_all = "kebab-case")]
+pub enum WatchAction {
+    #[default]
+    Restart,
+    RunTask,
+}
+
+fn default_debounce_ms() -> u64 {
+    300
+}
+
+/// A service's setup step: a command string, or a mapping listing the files
+/// whose changes make it run again.
+#[derive(Debug, Deserialize, Serialize, Clone)]
+#[serde(from = "SetupDef")]
+pub struct Setup {
+    pub command: String,
+    /// Globs relative to the service directory; defaults to well-known
+    /// lockfiles such as package-lock.json or Cargo.lock
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub watch: Vec<String>,
+}
+
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum SetupDef {
+    Command(String),
+    Full {
+        command: String,
+        #[serde(default, deserialize_with = "one_or_many")]
+        watch: Vec<String>,
+    },
+}
+
+impl From<SetupDef> for Setup {
+    fn from(def: SetupDef) -> Self {
+        match def {
+            SetupDef::Command(command) => Setup { command, watch: Vec::new() },
+            SetupDef::Full { command, watch } => Setup { command, watch },
+        }
+    }
+}
+
+fn default_enabled() -> bool {
+    true
+}
+
+fn default_replicas() -> u32 {
+    1
+}
+
+/// Accepts either a single string or a list of strings.
+fn one_or_many<'de, D>(deserializer: D) -> Result<Vec<String>, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum OneOrMany {
+        One(String),
+        Many(Vec<String>),
+    }
+
+    Ok(match OneOrMany::deserialize(deserializer)? {
+        OneOrMany::One(value) => vec![value],
+        OneOrMany::Many(values) => values,
+    })
+}
+
+/// Per-process resource limits. Each one caps every process of the service
+/// separately, except `processes`, which counts all of the user's processes.
+#[derive(Debug, Deserialize, Serialize, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Limits {
+    /// Maximum open file descriptors
+    pub open_files: Option<u64>,
+    /// Maximum address space, in bytes or with a K/M/G suffix (e.g. `512M`)
+    #[serde(default, deserialize_with = "byte_size")]
+    pub memory: Option<u64>,
+    /// CPU time in seconds before the process gets SIGXCPU
+    pub cpu_seconds: Option<u64>,
+    /// Set to false to disable core dumps
+    pub core_dumps: Option<bool>,
+    /// Maximum processes for the user, counted across the whole system
+    pub processes: Option<u64>,
+}
+
+/// Accepts a byte count or a string with a K, M or G suffix (powers of 1024).
+fn byte_size<'de, D>(deserializer: D) -> Result<Option<u64>, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum Size {
+        Bytes(u64),
+        Text(String),
+    }
+
+    let text = match Option::<Size>::deserialize(deserializer)? {
+        None => return Ok(None),
+        Some(Size::Bytes(bytes)) => return Ok(Some(bytes)),
+        Some(Size::Text(text)) => text,
+    };
+
+    let trimmed = text.trim().trim_end_matches(['B', 'b']);
+    let (number, multiplier) = match trimmed.char_indices().last() {
+        Some((i, 'K' | 'k')) => (&trimmed[..i], 1u64 << 10),
+        Some((i, 'M' | 'm')) => (&trimmed[..i], 1 << 20),
+        Some((i, 'G' | 'g')) => (&trimmed[..i], 1 << 30),
+        _ => (trimmed, 1),
+    };
+    number
+        .trim()
+        .parse::<u64>()
+        .ok()
+        .and_then(|number| number.checked_mul(multiplier))
+        .map(Some)
+        .ok_or_else(|| serde::de::Error::custom(format!("invalid size '{}'", text)))
+}
+
+/// How long `start` waits for a health check when none is configured.
+const DEFAULT_HEALTH_TIMEOUT_SECS: u64 = 30;
+
 #[derive(Debug, Deserialize, Serialize, Clone)]
 pub struct HealthCheck {
     pub type_entry: String,
-    pub port: Option<i16>,
-    pub http_target: String
-} 
+    /// Defaults to the service's `port` when omitted
+    pub port: Option<u16>,
+    /// May reference variables such as `${PORT}` or `${API_PORT}`
+    pub http_target: String,
+    /// Seconds to wait for the check to pass when starting (default 30)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub timeout: Option<u64>,
+}
+
+impl HealthCheck {
+    /// Fills in the port and expands `${VAR}` references from the service's environment.
+    pub fn resolve(&self, vars: &HashMap<String, String>) -> HealthCheck {
+        HealthCheck {
+            type_entry: self.type_entry.clone(),
+            port: self.port.or_else(|| vars.get("PORT").and_then(|port| port.parse().ok())),
+            http_target: crate::configs::env::expand_vars(&self.http_target, vars),
+            timeout: self.timeout,
+        }
+    }
+
+    pub fn timeout_secs(&self) -> u64 {
+        self.timeout.unwrap_or(DEFAULT_HEALTH_TIMEOUT_SECS)
+    }
+}
+
+/// A port a service listens on: a fixed number, or `auto` for devspin to pick
+/// a free one and keep it stable across restarts.
+#[derive(Debug, Deserialize, Serialize, Clone, Copy, PartialEq, Eq)]
+#[serde(untagged)]
+pub enum PortSpec {
+    Fixed(u16),
+    Auto(AutoPort),
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum AutoPort {
+    Auto,
+}
+
+impl std::fmt::Display for PortSpec {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            PortSpec::Fixed(port) => write!(f, "{}", port),
+            PortSpec::Auto(_) => write!(f, "auto"),
+        }
+    }
+}
 
 #[derive(Debug, Deserialize, Serialize, Clone)]
 pub struct Hooks {
     pub pre_start: Option<String>,
     pub post_start: Option<String>,
     pub pre_stop: Option<String>,
-    pub post_stop: Option<String>
+    pub post_stop: Option<String>,
+
+    /// What a failing or timed out hook does: `abort` (default) or `warn`
+    #[serde(default)]
+    pub on_failure: HookFailure,
+
+    /// Seconds a hook may run before it is killed and counted as failed
+    #[serde(default = "default_hook_timeout")]
+    pub timeout: u64,
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone, Copy, Default, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum HookFailure {
+    #[default]
+    Abort,
+    Warn,
+}
+
+fn default_hook_timeout() -> u64 {
+    60
 }
 
 impl ProjectConfig {
     pub fn from_file(path: &str) -> Result<Self, Box<dyn std::error::Error>> {  // FIXED: Add error type
-        let content = std::fs::read_to_string(path)?;
-        let mut config: ProjectConfig = serde_yaml::from_str(&content)?;
-        
-        // Store the config file directory as base path
-        config.base_path = Some(
-            std::path::Path::new(path)
-                .parent()
-                .map(|p| p.to_path_buf())
-                .unwrap_or_else(|| std::path::Path::new(".").to_path_buf())
-        );
-            
-        Ok(config)
+        Ok(Self::load(path, None)?)
+    }
+
+    /// Loads the config at `path` with the given profile applied on top.
+    pub fn load(path: &str, profile: Option<&str>) -> crate::error::Result<Self> {
+        crate::configs::loader::load_project(std::path::Path::new(path), profile)
     }
     
     pub fn resolve_path(&self, relative_path: &str) -> PathBuf {
@@ -85,4 +422,9 @@ impl ProjectConfig {
             PathBuf::from(relative_path)
         }
     }
+
+    /// Directory a service's command (and its hooks) run in.
+    pub fn service_dir(&self, service: &Service) -> PathBuf {
+        self.resolve_path(service.working_dir.as_deref().unwrap_or(""))
+    }
 }
\ No newline at end of file
@@ -0,0 +1,275 @@
+use std::collections::HashMap;
+use std::fmt;
+use std::time::{Duration, Instant};
+
+use nix::sys::signal::{kill, Signal};
+use nix::unistd::Pid;
+use sysinfo::{ProcessRefreshKind, System, UpdateKind};
+
+use crate::error::Result;
+use crate::process::runs::{is_pid_alive, RunRecord};
+
+/// Grace period for a stale process to release its port on SIGTERM.
+const KILL_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// A devspin service whose process tree holds a port.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DevspinOwner {
+    pub project: String,
+    pub service: String,
+    /// PID devspin recorded for the service (often the `sh -c` parent of the
+    /// listener), or the listener itself when the recorded one is gone
+    pub pid: u32,
+    /// Left behind by an earlier run: no live process in the project's run
+    /// record leads to it, only the `DEVSPIN_*` variables it was started with
+    pub stale: bool,
+}
+
+/// Whoever is listening on a port, as far as this user can see.
+#[derive(Debug, Clone)]
+pub struct PortOwner {
+    pub pid: Option<u32>,
+    pub command: Option<String>,
+    pub devspin: Option<DevspinOwner>,
+}
+
+impl fmt::Display for PortOwner {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match (self.pid, &self.command) {
+            (Some(pid), Some(command)) => write!(f, "PID {} ({})", pid, command)?,
+            (Some(pid), None) => write!(f, "PID {}", pid)?,
+            _ => write!(f, "a process not visible to this user")?,
+        }
+        if let Some(owner) = &self.devspin {
+            write!(f, ", service {} of devspin project '{}'", owner.service, owner.project)?;
+            if owner.stale {
+                write!(f, " (left over from an earlier run)")?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// A port a service needs that something else already holds.
+#[derive(Debug, Clone)]
+pub struct PortConflict {
+    pub port: u16,
+    pub needed_by: String,
+    pub owner: PortOwner,
+}
+
+/// Checks each `(port, needed_by)` pair and reports the ones already bound.
+pub fn find_conflicts(ports: &[(u16, String)]) -> Vec<PortConflict> {
+    let listening = listening_sockets();
+    let bound: Vec<&(u16, String)> = ports
+        .iter()
+        .filter(|(port, _)| listening.contains_key(port) || !port_check::is_local_port_free(*port))
+        .collect();
+    if bound.is_empty() {
+        return Vec::new();
+    }
+
+    let socket_pids = socket_pids();
+    let mut system = System::new();
+    system.refresh_processes_specifics(
+        ProcessRefreshKind::new().with_cmd(UpdateKind::Always).with_environ(UpdateKind::Always),
+    );
+    let records = RunRecord::all();
+
+    bound
+        .into_iter()
+        .map(|(port, needed_by)| {
+            let pid = listening.get(port).and_then(|inode| socket_pids.get(inode)).copied();
+            PortConflict {
+                port: *port,
+                needed_by: needed_by.clone(),
+                owner: PortOwner {
+                    pid,
+                    command: pid.and_then(|pid| command_of(&system, pid)),
+                    devspin: pid.and_then(|pid| devspin_owner(&system, &records, pid)),
+                },
+            }
+        })
+        .collect()
+}
+
+/// Stops a devspin service holding a port: SIGTERM to the recorded process
+/// and the listener, SIGKILL if the port is still bound after a grace period.
+pub fn kill_owner(conflict: &PortConflict) -> Result<()> {
+    let Some(owner) = &conflict.owner.devspin else { return Ok(()) };
+    let pids: Vec<u32> = std::iter::once(owner.pid).chain(conflict.owner.pid).collect();
+
+    for pid in &pids {
+        let _ = kill(Pid::from_raw(*pid as i32), Signal::SIGTERM);
+    }
+
+    let deadline = Instant::now() + KILL_TIMEOUT;
+    while pids.iter().any(|pid| is_pid_alive(*pid)) && Instant::now() < deadline {
+        std::thread::sleep(Duration::from_millis(100));
+    }
+    for pid in pids.iter().filter(|pid| is_pid_alive(**pid)) {
+        let _ = kill(Pid::from_raw(*pid as i32), Signal::SIGKILL);
+    }
+
+    RunRecord::forget_pid(&owner.project, owner.pid)
+}
+
+fn command_of(system: &System, pid: u32) -> Option<String> {
+    let process = system.process(sysinfo::Pid::from_u32(pid))?;
+    let command = process.cmd().join(" ");
+    Some(if command.is_empty() { process.name().to_string() } else { command })
+}
+
+/// The devspin service whose recorded process is `pid` or one of its
+/// ancestors. Failing that, a process devspin started whose recorded parent
+/// has exited (it was reparented, so the ancestry no longer leads to the
+/// record) is found by the variables every service is spawned with.
+fn devspin_owner(system: &System, records: &[RunRecord], pid: u32) -> Option<DevspinOwner> {
+    let mut current = Some(pid);
+    // Services run under `sh -c`, so the listener is usually a child or grandchild
+    for _ in 0..8 {
+        let Some(ancestor) = current else { break };
+        for record in records {
+            if let Some(service) = record.services.iter().find(|service| service.pid == ancestor && service.is_alive()) {
+                return Some(DevspinOwner {
+                    project: record.project.clone(),
+                    service: service.service_name.clone(),
+                    pid: ancestor,
+                    stale: false,
+                });
+            }
+        }
+        current = system
+            .process(sysinfo::Pid::from_u32(ancestor))
+            .and_then(|process| process.parent())
+            .map(|parent| parent.as_u32());
+    }
+
+    let environ = system.process(sysinfo::Pid::from_u32(pid))?.environ();
+    let var = |name: &str| environ.iter().find_map(|entry| entry.strip_prefix(name)?.strip_prefix('=')).map(str::to_string);
+    Some(DevspinOwner {
+        project: var("DEVSPIN_PROJECT")?,
+        service: var("DEVSPIN_SERVICE")?,
+        pid,
+        stale: true,
+    })
+}
+
+/// Listening TCP ports to socket inodes, from `/proc/net/tcp` and `tcp6`.
+/// Empty where `/proc` isn't available.
+fn listening_sockets() -> HashMap<u16, u64> {
+    ["/proc/net/tcp", "/proc/net/tcp6"]
+        .iter()
+        .filter_map(|path| std::fs::read_to_string(path).ok())
+        .flat_map(|content| parse_proc_net_tcp(&content))
+        .collect()
+}
+
+/// Parses `/proc/net/tcp` rows in the LISTEN state into `(port, inode)`.
+fn parse_proc_net_tcp(content: &str) -> Vec<(u16, u64)> {
+    const LISTEN: &str = "0A";
+
+    content
+        .lines()
+        .skip(1)
+        .filter_map(|line| {
+            let fields: Vec<&str> = line.split_whitespace().collect();
+            if fields.get(3) != Some(&LISTEN) {
+                return None;
+            }
+            let port = fields.get(1)?.rsplit(':').next()?;
+            let port = u16::from_str_radix(port, 16).ok()?;
+            let inode = fields.get(9)?.parse().ok()?;
+            Some((port, inode))
+        })
+        .collect()
+}
+
+/// Socket inodes to the PID holding them, for every process whose file
+/// descriptors this user may read.
+fn socket_pids() -> HashMap<u64, u32> {
+    let Ok(entries) = std::fs::read_dir("/proc") else {
+        return HashMap::new();
+    };
+
+    let mut pids = HashMap::new();
+    for entry in entries.filter_map(|entry| entry.ok()) {
+        let Some(pid) = entry.file_name().to_str().and_then(|name| name.parse::<u32>().ok()) else { continue };
+        let Ok(fds) = std::fs::read_dir(entry.path().join("fd")) else { continue };
+
+        for fd in fds.filter_map(|fd| fd.ok()) {
+            let Ok(target) = std::fs::read_link(fd.path()) else { continue };
+            let target = target.to_string_lossy();
+            if let Some(inode) = target.strip_prefix("socket:[").and_then(|rest| rest.strip_suffix(']')) {
+                if let Ok(inode) = inode.parse() {
+                    pids.insert(inode, pid);
+                }
+            }
+        }
+    }
+    pids
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::process::runs::ServiceRecord;
+
+    #[test]
+    fn test_parse_proc_net_tcp_keeps_listeners() {
+        let content = "\
+  sl  local_address rem_address   st tx_queue rx_queue tr tm->when retrnsmt   uid  timeout inode
+   0: 0100007F:0BB8 00000000:0000 0A 00000000:00000000 00:00000000 00000000  1000        0 41234 1 0000000000000000 100 0 0 10 0
+   1: 0100007F:9C40 0100007F:0BB8 01 00000000:00000000 00:00000000 00000000  1000        0 41299 1 0000000000000000 20 4 30 10 -1
+";
+        assert_eq!(parse_proc_net_tcp(content), vec![(3000, 41234)]);
+    }
+
+    #[test]
+    fn test_owner_is_stale_without_a_live_record() {
+        let mut child = std::process::Command::new("sleep")
+            .arg("30")
+            .env("DEVSPIN_PROJECT", "conflicts-shop")
+            .env("DEVSPIN_SERVICE", "web")
+            .spawn()
+            .unwrap();
+        let pid = child.id();
+        let mut system = System::new();
+        system.refresh_processes_specifics(ProcessRefreshKind::new().with_environ(UpdateKind::Always));
+
+        let record = RunRecord {
+            project: "conflicts-shop".to_string(),
+            services: vec![ServiceRecord {
+                service_name: "web".to_string(),
+                replica: 0,
+                pid,
+                container_id: None,
+                command: "sleep 30".to_string(),
+                started_at: std::time::SystemTime::now().duration_since(std::time::UNIX_EPOCH).unwrap().as_secs(),
+            }],
+            ..Default::default()
+        };
+        let live = devspin_owner(&system, std::slice::from_ref(&record), pid).unwrap();
+        let stale = devspin_owner(&system, &[], pid).unwrap();
+        child.kill().unwrap();
+        child.wait().unwrap();
+
+        assert!(!live.stale);
+        assert_eq!(live.pid, pid);
+        assert!(stale.stale);
+        assert_eq!((stale.project.as_str(), stale.service.as_str(), stale.pid), ("conflicts-shop", "web", pid));
+        assert!(devspin_owner(&system, &[], std::process::id()).is_none());
+    }
+
+    #[test]
+    fn test_finds_owner_of_bound_port() {
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let port = listener.local_addr().unwrap().port();
+
+        let conflicts = find_conflicts(&[(port, "web".to_string())]);
+        assert_eq!(conflicts.len(), 1);
+        if std::path::Path::new("/proc/net/tcp").exists() {
+            assert_eq!(conflicts[0].owner.pid, Some(std::process::id()));
+        }
+    }
+}
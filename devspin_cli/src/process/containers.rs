@@ -0,0 +1,319 @@
+use std::path::Path;
+use std::process::{Child, Command, Stdio};
+
+use crate::configs::env::ResolvedEnv;
+use crate::configs::yaml_parser::{PortSpec, Service};
+use crate::error::{Result, ToolError};
+use crate::process::ports::env_name;
+
+/// The docker CLI, looked up on PATH.
+const DOCKER: &str = "docker";
+
+/// Deterministic container name, so a restart replaces the old container
+/// rather than piling up new ones: `devspin-<project>-<service>[-<replica>]`.
+pub fn container_name(project: &str, service: &str, replica: u32) -> String {
+    let mut name = format!("devspin-{}-{}", project, service);
+    if replica > 0 {
+        name.push_str(&format!("-{}", replica));
+    }
+    name.chars()
+        .map(|c| if c.is_ascii_alphanumeric() || c == '.' || c == '-' || c == '_' { c.to_ascii_lowercase() } else { '-' })
+        .collect()
+}
+
+/// Arguments to `docker run --detach` for one replica.
+pub fn run_args(project: &str, service: &Service, replica: u32, env: &ResolvedEnv, working_dir: &Path) -> Vec<String> {
+    let mut args: Vec<String> = vec!["run".into(), "--detach".into()];
+    args.extend(container_args(project, service, replica, env, working_dir));
+    args
+}
+
+/// Everything `docker run` needs after its own options. Variables are passed
+/// by name only (`-e KEY`) with their values in docker's own environment, so
+/// secrets never show up in the process list.
+pub fn container_args(project: &str, service: &Service, replica: u32, env: &ResolvedEnv, working_dir: &Path) -> Vec<String> {
+    let mut args: Vec<String> = vec![
+        "--name".into(),
+        container_name(project, &service.name, replica),
+        "--label".into(),
+        format!("devspin.project={}", project),
+        "--label".into(),
+        format!("devspin.service={}", service.name),
+    ];
+
+    // Host ports are the replica's own, already offset by `replica_env`. Inside
+    // the container a fixed port stays as declared; an auto port is only
+    // known to the container through its variable, which holds the host port.
+    let host_port = |var: &str| env.vars.get(var).and_then(|port| port.parse::<u16>().ok());
+    let target = |spec: PortSpec, host: u16| match spec {
+        PortSpec::Fixed(port) => port,
+        PortSpec::Auto(_) => host,
+    };
+    if let (Some(spec), Some(port)) = (service.port, host_port("PORT")) {
+        args.push("--publish".into());
+        args.push(format!("{}:{}", port, service.container_port.unwrap_or_else(|| target(spec, port))));
+    }
+    for (name, spec) in &service.ports {
+        if let Some(port) = host_port(&format!("{}_PORT", env_name(name))) {
+            args.push("--publish".into());
+            args.push(format!("{}:{}", port, target(*spec, port)));
+        }
+    }
+
+    for volume in &service.volumes {
+        args.push("--volume".into());
+        args.push(resolve_volume(volume, working_dir));
+    }
+
+    let mut keys: Vec<&String> = env.vars.keys().collect();
+    keys.sort();
+    for key in keys {
+        args.push("--env".into());
+        args.push(key.clone());
+    }
+
+    args.push(service.image.clone().unwrap_or_default());
+    if !service.command.trim().is_empty() {
+        args.extend(["sh".into(), "-c".into(), service.command.clone()]);
+    }
+    args
+}
+
+/// Makes a relative host path absolute; named volumes are left alone.
+fn resolve_volume(volume: &str, working_dir: &Path) -> String {
+    match volume.split_once(':') {
+        Some((host, rest)) if host.starts_with('.') => {
+            let host = host.strip_prefix("./").unwrap_or(host);
+            format!("{}:{}", working_dir.join(host).display(), rest)
+        }
+        _ => volume.to_string(),
+    }
+}
+
+/// Starts one replica of a container service, replacing any container left
+/// with the same name, and returns the new container's ID.
+pub fn run(project: &str, service: &Service, replica: u32, env: &ResolvedEnv, working_dir: &Path) -> Result<String> {
+    let name = container_name(project, &service.name, replica);
+    // A leftover container from an earlier run would block the name
+    let _ = docker().args(["rm", "--force", &name]).output();
+
+    let output = docker()
+        .args(run_args(project, service, replica, env, working_dir))
+        .envs(&env.vars)
+        .output()?;
+    if !output.status.success() {
+        return Err(ToolError::ProcessError(format!(
+            "docker run failed for {}: {}", name, String::from_utf8_lossy(&output.stderr).trim()
+        )));
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).trim().to_string())
+}
+
+/// Whether the container exists and is running.
+pub fn is_running(id: &str) -> bool {
+    docker()
+        .args(["inspect", "--format", "{{.State.Running}}", id])
+        .output()
+        .is_ok_and(|output| output.status.success() && String::from_utf8_lossy(&output.stdout).trim() == "true")
+}
+
+/// `docker stop`, which sends SIGTERM and kills after `timeout` seconds,
+/// then removes the container.
+pub fn stop(id: &str, timeout: u64) -> Result<()> {
+    let output = docker().args(["stop", "--time", &timeout.to_string(), id]).output()?;
+    if !output.status.success() {
+        return Err(ToolError::ProcessError(format!(
+            "docker stop failed for {}: {}", short_id(id), String::from_utf8_lossy(&output.stderr).trim()
+        )));
+    }
+    let _ = docker().args(["rm", id]).output();
+    Ok(())
+}
+
+/// Kills and removes the container at once.
+pub fn remove(id: &str) -> Result<()> {
+    let output = docker().args(["rm", "--force", id]).output()?;
+    if !output.status.success() {
+        return Err(ToolError::ProcessError(format!(
+            "docker rm failed for {}: {}", short_id(id), String::from_utf8_lossy(&output.stderr).trim()
+        )));
+    }
+    Ok(())
+}
+
+/// The last `count` lines the container wrote to stdout or stderr.
+pub fn logs(id: &str, count: usize) -> Vec<String> {
+    let Ok(output) = docker().args(["logs", "--tail", &count.to_string(), id]).output() else {
+        return Vec::new();
+    };
+    let mut lines: Vec<String> = String::from_utf8_lossy(&output.stdout).lines().map(str::to_string).collect();
+    lines.extend(String::from_utf8_lossy(&output.stderr).lines().map(str::to_string));
+    lines
+}
+
+/// Streams the container's output to the terminal until it stops.
+pub fn follow_logs(id: &str) -> Result<Child> {
+    Ok(docker().args(["logs", "--follow", "--tail", "0", id]).stdin(Stdio::null()).spawn()?)
+}
+
+/// The 12-character form docker itself prints.
+pub fn short_id(id: &str) -> &str {
+    &id[..id.len().min(12)]
+}
+
+fn docker() -> Command {
+    #[allow(unused_mut)]
+    let mut command = Command::new(DOCKER);
+    // Tests talk to a stub instead of whatever docker the machine has
+    #[cfg(test)]
+    command.env("PATH", tests::stub_path()).current_dir(tests::stub_dir());
+    command
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::os::unix::fs::PermissionsExt;
+    use std::sync::OnceLock;
+    use crate::process::runs::RunRecord;
+
+    /// Stands in for the docker CLI: logs every call, hands out IDs derived
+    /// from the container name, and tracks which containers are running.
+    const STUB: &str = r#"#!/bin/sh
+dir=$(dirname "$0")
+echo "$*" >> "$dir/calls"
+case "$1" in
+  run)
+    name=$(echo "$*" | sed -n 's/.*--name \([^ ]*\).*/\1/p')
+    case "$*" in *" missing:image"*) echo "Unable to find image 'missing:image'" >&2; exit 125 ;; esac
+    touch "$dir/running-$name-id"
+    echo "$name-id" ;;
+  inspect)
+    for id; do :; done
+    [ -f "$dir/running-$id" ] || { echo "No such object: $id" >&2; exit 1; }
+    echo true ;;
+  stop|rm)
+    for id; do :; done
+    rm -f "$dir/running-$id" ;;
+  logs)
+    echo "ready to accept connections"
+    echo "warning: no config file" >&2 ;;
+esac
+"#;
+
+    pub fn stub_dir() -> &'static Path {
+        static DIR: OnceLock<tempfile::TempDir> = OnceLock::new();
+        DIR.get_or_init(|| {
+            let dir = tempfile::TempDir::new().unwrap();
+            let stub = dir.path().join(DOCKER);
+            std::fs::write(&stub, STUB).unwrap();
+            std::fs::set_permissions(&stub, std::fs::Permissions::from_mode(0o755)).unwrap();
+            dir
+        })
+        .path()
+    }
+
+    pub fn stub_path() -> String {
+        format!("{}:{}", stub_dir().display(), std::env::var("PATH").unwrap_or_default())
+    }
+
+    fn calls_for(id: &str) -> Vec<String> {
+        std::fs::read_to_string(stub_dir().join("calls"))
+            .unwrap_or_default()
+            .lines()
+            .filter(|call| call.contains(id))
+            .map(str::to_string)
+            .collect()
+    }
+
+    fn cache(image: &str) -> Service {
+        serde_yaml::from_str(&format!("{{ name: cache, kind: container, image: '{}', port: 6379, dependencies: [] }}", image)).unwrap()
+    }
+
+    #[test]
+    fn test_run_stop_and_logs_use_docker() {
+        let mut env = ResolvedEnv::default();
+        env.insert("PORT", "6379".to_string());
+        env.insert("REDIS_PASSWORD", "hunter2".to_string());
+
+        let id = run("containers-run", &cache("redis:7"), 0, &env, Path::new("/srv/app")).unwrap();
+        assert_eq!(id, "devspin-containers-run-cache-id");
+        assert!(is_running(&id));
+        assert_eq!(logs(&id, 5), vec!["ready to accept connections", "warning: no config file"]);
+
+        stop(&id, 3).unwrap();
+        assert!(!is_running(&id));
+
+        let calls = calls_for("devspin-containers-run-cache");
+        assert_eq!(calls[0], "rm --force devspin-containers-run-cache");
+        assert!(calls[1].starts_with("run --detach --name devspin-containers-run-cache "));
+        // Values travel in docker's environment, never on the command line
+        assert!(calls[1].contains("--env REDIS_PASSWORD"));
+        assert!(!calls[1].contains("hunter2"));
+        assert!(calls.contains(&format!("stop --time 3 {}", id)));
+        assert!(calls.contains(&format!("rm {}", id)));
+    }
+
+    #[test]
+    fn test_failed_run_reports_docker_error() {
+        let err = run("containers-fail", &cache("missing:image"), 0, &ResolvedEnv::default(), Path::new("/srv/app")).unwrap_err();
+        assert!(err.to_string().contains("Unable to find image 'missing:image'"));
+    }
+
+    #[test]
+    fn test_run_record_tracks_containers() {
+        let project = "containers-record";
+        let id = run(project, &cache("redis:7"), 1, &ResolvedEnv::default(), Path::new("/srv/app")).unwrap();
+        RunRecord::record_container(project, None, "cache", 1, &id, "redis:7").unwrap();
+
+        let record = RunRecord::load(project).unwrap().unwrap();
+        let alive = record.alive_services();
+        assert_eq!(alive.len(), 1);
+        assert_eq!((alive[0].replica, alive[0].pid, alive[0].container_id.as_deref()), (1, 0, Some(id.as_str())));
+
+        remove(&id).unwrap();
+        assert!(calls_for(&id).contains(&format!("rm --force {}", id)));
+        assert!(!RunRecord::load(project).unwrap().unwrap().is_running());
+
+        RunRecord::forget_container(project, &id).unwrap();
+        assert!(RunRecord::load(project).unwrap().is_none());
+    }
+
+    #[test]
+    fn test_run_args() {
+        let service: Service = serde_yaml::from_str(
+            "{ name: cache, kind: container, image: 'redis:7', port: 4000, container_port: 6379, ports: { metrics: 9121 }, volumes: ['./data:/data', 'cache:/tmp'], dependencies: [] }",
+        ).unwrap();
+        let mut env = ResolvedEnv::default();
+        env.insert("PORT", "4001".to_string());
+        env.insert("METRICS_PORT", "9122".to_string());
+
+        let args = run_args("My App", &service, 1, &env, Path::new("/srv/app"));
+        let joined = args.join(" ");
+        assert!(joined.starts_with("run --detach --name devspin-my-app-cache-1 "));
+        assert!(joined.contains("--publish 4001:6379 --publish 9122:9121"));
+        assert!(joined.contains("--volume /srv/app/data:/data --volume cache:/tmp"));
+        assert!(joined.contains("--env METRICS_PORT --env PORT"));
+        assert!(joined.ends_with(" redis:7"));
+        assert!(!joined.contains("4001:4001"));
+    }
+
+    #[test]
+    fn test_fixed_ports_keep_their_container_side() {
+        let service: Service = serde_yaml::from_str(
+            "{ name: api, kind: container, image: app, port: 3000, ports: { debug: auto }, dependencies: [] }",
+        ).unwrap();
+        let mut env = ResolvedEnv::default();
+        env.insert("PORT", "3001".to_string());
+        env.insert("DEBUG_PORT", "4101".to_string());
+
+        let joined = run_args("shop", &service, 1, &env, Path::new("/srv/app")).join(" ");
+        assert!(joined.contains("--publish 3001:3000 --publish 4101:4101"));
+    }
+
+    #[test]
+    fn test_container_name_is_deterministic() {
+        assert_eq!(container_name("shop", "db", 0), "devspin-shop-db");
+        assert_eq!(container_name("shop", "db", 2), "devspin-shop-db-2");
+    }
+}
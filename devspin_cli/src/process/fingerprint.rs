@@ -0,0 +1,282 @@
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+use ignore::overrides::OverrideBuilder;
+use ignore::WalkBuilder;
+use serde::{Deserialize, Serialize};
+
+use crate::configs::paths::{file_stem, fingerprints_dir};
+use crate::configs::yaml_parser::Task;
+use crate::error::{Result, ToolError};
+
+/// Content hashes of a task's inputs and outputs after its last successful run.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+pub struct TaskFingerprint {
+    pub inputs: String,
+    pub outputs: String,
+}
+
+/// Fingerprints of every incremental task in one project, kept under the
+/// devspin home so they survive between runs without touching the project.
+#[derive(Debug, Default, Deserialize, Serialize)]
+pub struct FingerprintStore {
+    #[serde(skip)]
+    project: String,
+    tasks: BTreeMap<String, TaskFingerprint>,
+    /// Service name to the hash of its setup command and watched files
+    #[serde(default)]
+    setup: BTreeMap<String, String>,
+}
+
+impl FingerprintStore {
+    fn path(project: &str) -> PathBuf {
+        fingerprints_dir().join(format!("{}.json", file_stem(project)))
+    }
+
+    pub fn load(project: &str) -> Result<Self> {
+        let path = Self::path(project);
+        let mut store: Self = if path.exists() {
+            let content = std::fs::read_to_string(&path)?;
+            serde_json::from_str(&content).map_err(|e| ToolError::ParseError(format!(
+                "Invalid fingerprint file {}: {}", path.display(), e
+            )))?
+        } else {
+            Self::default()
+        };
+        store.project = project.to_string();
+        Ok(store)
+    }
+
+    pub fn save(&self) -> Result<()> {
+        std::fs::create_dir_all(fingerprints_dir())?;
+        let content = serde_json::to_string_pretty(self)
+            .map_err(|e| ToolError::GenericError(e.to_string()))?;
+        std::fs::write(Self::path(&self.project), content)?;
+        Ok(())
+    }
+
+    pub fn get(&self, task: &str) -> Option<&TaskFingerprint> {
+        self.tasks.get(task)
+    }
+
+    pub fn set(&mut self, task: &str, fingerprint: TaskFingerprint) {
+        self.tasks.insert(task.to_string(), fingerprint);
+    }
+
+    pub fn setup(&self, service: &str) -> Option<&str> {
+        self.setup.get(service).map(String::as_str)
+    }
+
+    pub fn set_setup(&mut self, service: &str, fingerprint: String) {
+        self.setup.insert(service.to_string(), fingerprint);
+    }
+}
+
+/// Hashes the files matched by the task's `inputs` and `outputs` globs,
+/// relative to `dir`. Returns `None` when the task declares no inputs and
+/// so always runs.
+///
+/// Inputs honor `.gitignore`; outputs do not, since build output is
+/// usually ignored. The command itself is part of the input hash so
+/// editing it reruns the task.
+pub fn fingerprint(dir: &Path, task: &Task) -> Result<Option<TaskFingerprint>> {
+    if task.inputs.is_empty() {
+        return Ok(None);
+    }
+
+    let mut inputs = Fnv::new();
+    inputs.write(task.command.as_deref().unwrap_or_default().as_bytes());
+    hash_files(dir, &task.inputs, true, &mut inputs)?;
+
+    let mut outputs = Fnv::new();
+    let output_count = if task.outputs.is_empty() {
+        0
+    } else {
+        hash_files(dir, &task.outputs, false, &mut outputs)?
+    };
+    // Declared outputs that don't exist can never be up to date
+    if !task.outputs.is_empty() && output_count == 0 {
+        outputs.write(b"missing");
+    }
+
+    Ok(Some(TaskFingerprint {
+        inputs: inputs.hex(),
+        outputs: outputs.hex(),
+    }))
+}
+
+/// Hash of `label` plus the files matched by `globs` under `dir`,
+/// honoring `.gitignore`.
+pub fn hash_globs(dir: &Path, label: &str, globs: &[String]) -> Result<String> {
+    let mut hasher = Fnv::new();
+    hasher.write(label.as_bytes());
+    if !globs.is_empty() {
+        hash_files(dir, globs, true, &mut hasher)?;
+    }
+    Ok(hasher.hex())
+}
+
+fn hash_files(dir: &Path, globs: &[String], respect_ignore: bool, hasher: &mut Fnv) -> Result<usize> {
+    let files = matching_files(dir, globs, respect_ignore)?;
+
+    for file in &files {
+        let relative = file.strip_prefix(dir).unwrap_or(file);
+        hasher.write(relative.to_string_lossy().as_bytes());
+        hasher.write(&[0]);
+        hasher.write(&std::fs::read(file)?);
+        hasher.write(&[0]);
+    }
+    Ok(files.len())
+}
+
+/// Files under `dir` matching `globs` (a leading `!` excludes), sorted.
+/// With `respect_ignore`, `.gitignore` and friends are honored as well.
+pub fn matching_files(dir: &Path, globs: &[String], respect_ignore: bool) -> Result<Vec<PathBuf>> {
+    let mut overrides = OverrideBuilder::new(dir);
+    for glob in globs {
+        overrides.add(glob).map_err(|e| ToolError::ConfigError(format!(
+            "Invalid glob '{}': {}", glob, e
+        )))?;
+    }
+    let overrides = overrides.build().map_err(|e| ToolError::ConfigError(e.to_string()))?;
+
+    // Match globs separately: as walker overrides they would beat .gitignore
+    let mut files: Vec<PathBuf> = Vec::new();
+    let max_depth = literal_depth(globs);
+    for root in glob_roots(dir, globs) {
+        let walker = WalkBuilder::new(&root)
+            .max_depth(max_depth)
+            .hidden(false)
+            .git_ignore(respect_ignore)
+            .git_exclude(respect_ignore)
+            .ignore(respect_ignore)
+            .parents(true)
+            .require_git(false)
+            .filter_entry(|entry| entry.file_name() != ".git")
+            .build();
+        files.extend(
+            walker
+                .filter_map(|entry| entry.ok())
+                .filter(|entry| entry.file_type().is_some_and(|kind| kind.is_file()))
+                .map(|entry| entry.into_path())
+                .filter(|path| overrides.matched(path, false).is_whitelist()),
+        );
+    }
+    files.sort();
+    files.dedup();
+    Ok(files)
+}
+
+/// How deep a walk has to go when every glob is an anchored literal path
+/// (`/package-lock.json`), so those don't scan the whole tree. `None` when
+/// any glob can match at an arbitrary depth.
+fn literal_depth(globs: &[String]) -> Option<usize> {
+    globs
+        .iter()
+        .filter(|glob| !glob.starts_with('!'))
+        .map(|glob| {
+            let anchored = glob.trim_end_matches('/').contains('/');
+            (anchored && !glob.contains(['*', '?', '[', '{'])).then(|| glob.trim_start_matches('/').split('/').count())
+        })
+        .try_fold(0, |depth, glob_depth| Some(depth.max(glob_depth?)))
+}
+
+/// Directories to walk: the literal prefix of each glob, so `dist/**`
+/// doesn't scan the whole project.
+fn glob_roots(dir: &Path, globs: &[String]) -> Vec<PathBuf> {
+    let mut roots: Vec<PathBuf> = globs
+        .iter()
+        .filter(|glob| !glob.starts_with('!'))
+        .map(|glob| {
+            let literal: Vec<&str> = glob
+                .split('/')
+                .take_while(|part| !part.contains(['*', '?', '[', '{']))
+                .collect();
+            // The last literal part may be the file itself
+            let prefix = if literal.len() == glob.split('/').count() {
+                &literal[..literal.len().saturating_sub(1)]
+            } else {
+                &literal[..]
+            };
+            dir.join(prefix.join("/"))
+        })
+        .filter(|root| root.is_dir())
+        .collect();
+
+    roots.sort();
+    roots.dedup();
+    // Nested roots would be walked twice
+    let all = roots.clone();
+    roots.retain(|root| !all.iter().any(|other| other != root && root.starts_with(other)));
+    roots
+}
+
+/// 64-bit FNV-1a; stable across runs and Rust versions, unlike `DefaultHasher`.
+struct Fnv(u64);
+
+impl Fnv {
+    fn new() -> Self {
+        Fnv(0xcbf2_9ce4_8422_2325)
+    }
+
+    fn write(&mut self, bytes: &[u8]) {
+        for byte in bytes {
+            self.0 ^= u64::from(*byte);
+            self.0 = self.0.wrapping_mul(0x0100_0000_01b3);
+        }
+    }
+
+    fn hex(&self) -> String {
+        format!("{:016x}", self.0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn task(inputs: &[&str], outputs: &[&str]) -> Task {
+        Task {
+            command: Some("build".to_string()),
+            inputs: inputs.iter().map(|s| s.to_string()).collect(),
+            outputs: outputs.iter().map(|s| s.to_string()).collect(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_fingerprint_tracks_inputs_and_outputs() {
+        let dir = TempDir::new().unwrap();
+        std::fs::create_dir_all(dir.path().join("src")).unwrap();
+        std::fs::write(dir.path().join("src/main.rs"), "fn main() {}").unwrap();
+        std::fs::write(dir.path().join("README.md"), "docs").unwrap();
+        let task = task(&["src/**/*.rs"], &["dist/*"]);
+
+        let missing_output = fingerprint(dir.path(), &task).unwrap().unwrap();
+        std::fs::create_dir_all(dir.path().join("dist")).unwrap();
+        std::fs::write(dir.path().join("dist/app"), "bin").unwrap();
+        let built = fingerprint(dir.path(), &task).unwrap().unwrap();
+        assert_ne!(missing_output.outputs, built.outputs);
+
+        // Files outside the globs don't matter
+        std::fs::write(dir.path().join("README.md"), "more docs").unwrap();
+        assert_eq!(fingerprint(dir.path(), &task).unwrap().unwrap(), built);
+
+        std::fs::write(dir.path().join("src/main.rs"), "fn main() { }").unwrap();
+        assert_ne!(fingerprint(dir.path(), &task).unwrap().unwrap().inputs, built.inputs);
+    }
+
+    #[test]
+    fn test_gitignored_inputs_are_skipped() {
+        let dir = TempDir::new().unwrap();
+        std::fs::write(dir.path().join(".gitignore"), "generated.txt\n").unwrap();
+        std::fs::write(dir.path().join("input.txt"), "a").unwrap();
+        let task = task(&["*.txt"], &[]);
+
+        let before = fingerprint(dir.path(), &task).unwrap().unwrap();
+        std::fs::write(dir.path().join("generated.txt"), "noise").unwrap();
+        assert_eq!(fingerprint(dir.path(), &task).unwrap().unwrap(), before);
+        assert!(fingerprint(dir.path(), &Task::default()).unwrap().is_none());
+    }
+}
@@ -0,0 +1,106 @@
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+
+use tokio::net::TcpStream;
+
+use crate::configs::yaml_parser::{HealthCheck, PortSpec, Service};
+use crate::error::{Result, ToolError};
+use crate::process::ports::env_name;
+
+/// How long one probe may take before it counts as a failure.
+const PROBE_TIMEOUT: Duration = Duration::from_secs(2);
+
+/// Pause between failed probes while waiting for a service.
+const RETRY_INTERVAL: Duration = Duration::from_millis(500);
+
+/// Checks a service once. `Err` says why it isn't healthy yet.
+pub async fn probe(check: &HealthCheck) -> std::result::Result<(), String> {
+    match check.type_entry.as_str() {
+        "http" => probe_http(&check.http_target).await,
+        "port" => match check.port {
+            Some(port) => probe_port(port).await,
+            None => Err("no port to check".to_string()),
+        },
+        other => Err(format!("unknown health check type '{}'", other)),
+    }
+}
+
+/// Probes until the check passes or its timeout runs out.
+pub async fn wait_until_healthy(service: &str, check: &HealthCheck) -> Result<()> {
+    let timeout = Duration::from_secs(check.timeout_secs());
+    let deadline = Instant::now() + timeout;
+    loop {
+        let error = match probe(check).await {
+            Ok(()) => return Ok(()),
+            Err(error) => error,
+        };
+        if Instant::now() >= deadline {
+            return Err(ToolError::ProcessError(format!(
+                "Health check for {} did not pass within {}s: {}", service, timeout.as_secs(), error
+            )));
+        }
+        tokio::time::sleep(RETRY_INTERVAL).await;
+    }
+}
+
+/// Variables an external service's health check can use without resolving
+/// the project's environment: the caller's own plus its fixed ports.
+pub fn external_vars(service: &Service) -> HashMap<String, String> {
+    let mut vars: HashMap<String, String> = std::env::vars().collect();
+    if let Some(PortSpec::Fixed(port)) = service.port {
+        vars.insert("PORT".to_string(), port.to_string());
+    }
+    for (name, port) in &service.ports {
+        if let PortSpec::Fixed(port) = port {
+            vars.insert(format!("{}_PORT", env_name(name)), port.to_string());
+        }
+    }
+    vars
+}
+
+async fn probe_port(port: u16) -> std::result::Result<(), String> {
+    match tokio::time::timeout(PROBE_TIMEOUT, TcpStream::connect(("localhost", port))).await {
+        Ok(Ok(_)) => Ok(()),
+        Ok(Err(e)) => Err(format!("port {}: {}", port, e)),
+        Err(_) => Err(format!("port {}: timed out", port)),
+    }
+}
+
+/// Any response below 400 counts as healthy.
+async fn probe_http(target: &str) -> std::result::Result<(), String> {
+    let client = reqwest::Client::builder()
+        .timeout(PROBE_TIMEOUT)
+        .build()
+        .map_err(|e| e.to_string())?;
+    let response = client.get(target).send().await.map_err(|e| format!("{}: {}", target, e))?;
+    let status = response.status();
+    if status.is_client_error() || status.is_server_error() {
+        return Err(format!("{} returned {}", target, status));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn port_check(port: u16) -> HealthCheck {
+        HealthCheck { type_entry: "port".to_string(), port: Some(port), http_target: String::new(), timeout: Some(1) }
+    }
+
+    #[tokio::test]
+    async fn test_port_probe() {
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let port = listener.local_addr().unwrap().port();
+        assert!(probe(&port_check(port)).await.is_ok());
+
+        drop(listener);
+        assert!(wait_until_healthy("db", &port_check(port)).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_unknown_type_is_unhealthy() {
+        let check = HealthCheck { type_entry: "grpc".to_string(), ..port_check(1) };
+        assert_eq!(probe(&check).await, Err("unknown health check type 'grpc'".to_string()));
+    }
+}
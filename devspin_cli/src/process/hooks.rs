@@ -0,0 +1,166 @@
+use std::collections::HashMap;
+use std::path::Path;
+use std::time::Duration;
+
+use colored::*;
+
+use crate::configs::secrets::Redactor;
+use crate::configs::yaml_parser::{HookFailure, Hooks};
+use crate::error::{Result, ToolError};
+
+/// Points in a project's or service's lifecycle where a hook can run.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HookStage {
+    PreStart,
+    PostStart,
+    PreStop,
+    PostStop,
+}
+
+impl HookStage {
+    pub fn name(&self) -> &'static str {
+        match self {
+            HookStage::PreStart => "pre_start",
+            HookStage::PostStart => "post_start",
+            HookStage::PreStop => "pre_stop",
+            HookStage::PostStop => "post_stop",
+        }
+    }
+
+    fn command<'a>(&self, hooks: &'a Hooks) -> Option<&'a str> {
+        match self {
+            HookStage::PreStart => hooks.pre_start.as_deref(),
+            HookStage::PostStart => hooks.post_start.as_deref(),
+            HookStage::PreStop => hooks.pre_stop.as_deref(),
+            HookStage::PostStop => hooks.post_stop.as_deref(),
+        }
+    }
+}
+
+/// Where and with what environment a hook runs.
+pub struct HookContext<'a> {
+    pub project: &'a str,
+    /// Set for service-level hooks
+    pub service: Option<&'a str>,
+    pub working_dir: &'a Path,
+    pub env: &'a HashMap<String, String>,
+    pub redactor: &'a Redactor,
+    pub verbose: bool,
+}
+
+/// Runs the hook configured for `stage`, if any.
+///
+/// The hook gets the resolved environment plus `DEVSPIN_PROJECT`,
+/// `DEVSPIN_HOOK` and, for service hooks, `DEVSPIN_SERVICE`. A non-zero
+/// exit or a timeout is an error when `on_failure` is `abort`, and only a
+/// warning when it is `warn`.
+pub async fn run_hook(hooks: Option<&Hooks>, stage: HookStage, ctx: &HookContext<'_>) -> Result<()> {
+    let Some(hooks) = hooks else { return Ok(()) };
+    let Some(command) = stage.command(hooks) else { return Ok(()) };
+
+    let label = match ctx.service {
+        Some(service) => format!("{} hook for {}", stage.name(), service),
+        None => format!("{} hook", stage.name()),
+    };
+    println!("{} {}", "Running".dimmed(), label.bold());
+    if ctx.verbose {
+        println!("  {} {}", "Command:".dimmed(), ctx.redactor.redact(command).dimmed());
+    }
+
+    let outcome = execute(command, stage, hooks.timeout, ctx).await;
+
+    match (outcome, hooks.on_failure) {
+        (Ok(()), _) => Ok(()),
+        (Err(reason), HookFailure::Abort) => Err(ToolError::ProcessError(format!(
+            "{} failed: {}", label, reason
+        ))),
+        (Err(reason), HookFailure::Warn) => {
+            eprintln!("{} {}", "WARN:".yellow(), format!("{} failed: {}", label, reason).yellow());
+            Ok(())
+        }
+    }
+}
+
+async fn execute(command: &str, stage: HookStage, timeout: u64, ctx: &HookContext<'_>) -> std::result::Result<(), String> {
+    let mut process = tokio::process::Command::new("sh");
+    process
+        .arg("-c")
+        .arg(command)
+        .current_dir(ctx.working_dir)
+        .envs(ctx.env)
+        .env("DEVSPIN_PROJECT", ctx.project)
+        .env("DEVSPIN_HOOK", stage.name())
+        .kill_on_drop(true);
+    if let Some(service) = ctx.service {
+        process.env("DEVSPIN_SERVICE", service);
+    }
+
+    let mut child = process.spawn().map_err(|e| format!("cannot run hook: {}", e))?;
+
+    match tokio::time::timeout(Duration::from_secs(timeout), child.wait()).await {
+        Ok(Ok(status)) if status.success() => Ok(()),
+        Ok(Ok(status)) => Err(format!("exited with {}", status)),
+        Ok(Err(e)) => Err(e.to_string()),
+        Err(_) => {
+            let _ = child.kill().await;
+            Err(format!("timed out after {}s", timeout))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn hooks(pre_start: &str, on_failure: HookFailure, timeout: u64) -> Hooks {
+        Hooks {
+            pre_start: Some(pre_start.to_string()),
+            post_start: None,
+            pre_stop: None,
+            post_stop: None,
+            on_failure,
+            timeout,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_hook_runs_with_context() {
+        let dir = TempDir::new().unwrap();
+        let env = HashMap::from([("GREETING".to_string(), "hi".to_string())]);
+        let redactor = Redactor::default();
+        let ctx = HookContext {
+            project: "app",
+            service: Some("api"),
+            working_dir: dir.path(),
+            env: &env,
+            redactor: &redactor,
+            verbose: false,
+        };
+
+        let hooks = hooks("echo \"$GREETING $DEVSPIN_PROJECT $DEVSPIN_SERVICE $DEVSPIN_HOOK\" > out", HookFailure::Abort, 5);
+        run_hook(Some(&hooks), HookStage::PreStart, &ctx).await.unwrap();
+
+        let out = std::fs::read_to_string(dir.path().join("out")).unwrap();
+        assert_eq!(out.trim(), "hi app api pre_start");
+    }
+
+    #[tokio::test]
+    async fn test_failure_policy() {
+        let dir = TempDir::new().unwrap();
+        let env = HashMap::new();
+        let redactor = Redactor::default();
+        let ctx = HookContext {
+            project: "app",
+            service: None,
+            working_dir: dir.path(),
+            env: &env,
+            redactor: &redactor,
+            verbose: false,
+        };
+
+        assert!(run_hook(Some(&hooks("exit 3", HookFailure::Abort, 5)), HookStage::PreStart, &ctx).await.is_err());
+        assert!(run_hook(Some(&hooks("exit 3", HookFailure::Warn, 5)), HookStage::PreStart, &ctx).await.is_ok());
+        assert!(run_hook(Some(&hooks("sleep 5", HookFailure::Abort, 1)), HookStage::PreStart, &ctx).await.is_err());
+    }
+}
@@ -0,0 +1,107 @@
+use std::os::unix::process::ExitStatusExt;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+use crate::configs::env::ResolvedEnv;
+use crate::configs::yaml_parser::Service;
+use crate::error::Result;
+use crate::process::replicas::{self, log_path};
+use crate::process::runs::JobRecord;
+
+/// How often a running job is checked for completion.
+const POLL_INTERVAL: Duration = Duration::from_millis(100);
+
+/// Runs a oneshot service to completion. Its output goes to the service's
+/// log file, which is emptied first so it only holds this run.
+pub async fn run_job(project: &str, service: &Service, env: &ResolvedEnv, working_dir: &str) -> Result<JobRecord> {
+    let log = log_path(project, &service.name, 0);
+    let _ = std::fs::remove_file(&log);
+
+    let started_at = SystemTime::now().duration_since(UNIX_EPOCH).map(|d| d.as_secs()).unwrap_or_default();
+    let started = Instant::now();
+    let mut child = replicas::spawn(project, service, 0, env, working_dir, Some(&log))?;
+
+    let status = loop {
+        if let Some(status) = child.try_wait()? {
+            break status;
+        }
+        tokio::time::sleep(POLL_INTERVAL).await;
+    };
+
+    Ok(JobRecord {
+        service_name: service.name.clone(),
+        started_at,
+        duration_ms: started.elapsed().as_millis() as u64,
+        // Shells report death by signal N as 128 + N; do the same
+        exit_code: status.code().or_else(|| status.signal().map(|signal| 128 + signal)).unwrap_or(-1),
+    })
+}
+
+/// Short human form of a job's run time, e.g. "850ms", "4.2s" or "3m 5s".
+pub fn format_duration(duration: Duration) -> String {
+    let millis = duration.as_millis();
+    if millis < 1000 {
+        format!("{}ms", millis)
+    } else if millis < 60_000 {
+        format!("{:.1}s", duration.as_secs_f64())
+    } else {
+        format!("{}m {}s", duration.as_secs() / 60, duration.as_secs() % 60)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::process::replicas::tail_lines;
+    use crate::process::runs::RunRecord;
+
+    fn job(name: &str, command: &str) -> Service {
+        serde_yaml::from_str(&format!("{{ name: {}, kind: oneshot, command: {:?}, dependencies: [] }}", name, command)).unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_failing_job_keeps_its_output() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let service = job("migrate", "echo migrating; echo 'no such table' >&2; sh -c 'exit 3'");
+
+        let record = run_job("jobs-failing", &service, &ResolvedEnv::default(), &dir.path().to_string_lossy()).await.unwrap();
+        assert_eq!(record.exit_code, 3);
+        assert!(!record.succeeded());
+        assert_eq!(tail_lines(&log_path("jobs-failing", "migrate", 0), 20), vec!["migrating", "no such table"]);
+
+        // The next run starts from an empty log
+        let record = run_job("jobs-failing", &job("migrate", "true"), &ResolvedEnv::default(), &dir.path().to_string_lossy()).await.unwrap();
+        assert!(record.succeeded());
+        assert!(tail_lines(&log_path("jobs-failing", "migrate", 0), 20).is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_job_results_last_until_the_run_ends() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let working_dir = dir.path().to_string_lossy().to_string();
+        let project = "jobs-record";
+
+        let failed = run_job(project, &job("migrate", "sh -c 'exit 3'"), &ResolvedEnv::default(), &working_dir).await.unwrap();
+        RunRecord::record_job(project, None, failed).unwrap();
+        let record = RunRecord::load(project).unwrap().unwrap();
+        assert_eq!(record.job("migrate").map(|job| job.exit_code), Some(3));
+
+        // A later run replaces the earlier result
+        let done = run_job(project, &job("migrate", "true"), &ResolvedEnv::default(), &working_dir).await.unwrap();
+        RunRecord::record_job(project, None, done).unwrap();
+        RunRecord::record_service(project, None, "api", 0, std::process::id(), "api").unwrap();
+        let record = RunRecord::load(project).unwrap().unwrap();
+        assert_eq!(record.jobs.len(), 1);
+        assert!(record.job("migrate").unwrap().succeeded());
+
+        // Once the last process is gone the run is over, jobs included
+        RunRecord::forget_pid(project, std::process::id()).unwrap();
+        assert!(RunRecord::load(project).unwrap().is_none());
+    }
+
+    #[test]
+    fn test_format_duration() {
+        assert_eq!(format_duration(Duration::from_millis(850)), "850ms");
+        assert_eq!(format_duration(Duration::from_millis(4210)), "4.2s");
+        assert_eq!(format_duration(Duration::from_secs(185)), "3m 5s");
+    }
+}
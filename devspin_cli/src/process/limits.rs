@@ -0,0 +1,184 @@
+use std::path::PathBuf;
+
+use nix::sys::resource::{getrlimit, setrlimit, Resource};
+use nix::sys::signal::Signal;
+
+use crate::configs::paths::{file_stem, runs_dir};
+use crate::configs::yaml_parser::Limits;
+
+/// Runs the service command in a child shell and records its exit code, so
+/// a later `status` can tell why a limited service died.
+///
+/// The wrapper's PID is the one devspin tracks and signals, so it forwards
+/// SIGTERM (and SIGINT/SIGHUP, which a background child would ignore) to
+/// the command and exits with it. A service stopped that way leaves no
+/// record. The command keeps the wrapper's stdin, which `&` would replace.
+pub const EXIT_WRAPPER: &str = r#"trap 'stopped=1; [ -z "$pid" ] || kill -TERM "$pid" 2>/dev/null' TERM INT HUP
+exec 3<&0
+sh -c "$1" <&3 3<&- &
+pid=$!
+exec 3<&-
+# Passes on a signal that arrived before the command's PID was known
+[ -z "$stopped" ] || kill -TERM "$pid" 2>/dev/null
+wait "$pid"; code=$?
+# A trapped signal cuts `wait` short; wait again for the command's own status
+while kill -0 "$pid" 2>/dev/null; do wait "$pid"; code=$?; done
+[ -n "$stopped" ] || echo "$code" > "$2"
+exit "$code""#;
+
+/// Output that means an allocation failed, for runtimes that exit cleanly on it.
+const OUT_OF_MEMORY_MARKERS: [&str; 4] = ["out of memory", "memoryerror", "cannot allocate memory", "memory allocation of"];
+
+/// Applies the limits to the current process. Runs in the child between
+/// fork and exec, so it only makes syscalls. Limits are clamped to the
+/// current hard limit, which an unprivileged process cannot raise.
+pub fn apply(limits: &Limits) -> std::io::Result<()> {
+    let cpu_hard = limits.cpu_seconds.map(|seconds| seconds.saturating_add(1));
+    let core = limits.core_dumps.and_then(|enabled| (!enabled).then_some(0));
+    let settings = [
+        (Resource::RLIMIT_NOFILE, limits.open_files, limits.open_files),
+        (Resource::RLIMIT_AS, limits.memory, limits.memory),
+        // SIGXCPU at the soft limit, SIGKILL a second later
+        (Resource::RLIMIT_CPU, limits.cpu_seconds, cpu_hard),
+        (Resource::RLIMIT_CORE, core, core),
+        (Resource::RLIMIT_NPROC, limits.processes, limits.processes),
+    ];
+
+    for (resource, soft, hard) in settings {
+        let (Some(soft), Some(hard)) = (soft, hard) else { continue };
+        let (_, current_hard) = getrlimit(resource)?;
+        setrlimit(resource, soft.min(current_hard), hard.min(current_hard))?;
+    }
+    Ok(())
+}
+
+/// Where a limited service replica's exit code is written.
+pub fn exit_file(project: &str, service: &str, replica: u32) -> PathBuf {
+    runs_dir().join("exits").join(format!("{}.{}.{}", file_stem(project), file_stem(service), replica))
+}
+
+/// The exit code recorded for a service replica that died on its own.
+pub fn read_exit(project: &str, service: &str, replica: u32) -> Option<i32> {
+    std::fs::read_to_string(exit_file(project, service, replica)).ok()?.trim().parse().ok()
+}
+
+/// Explains an exit code in terms of the limits that were in force, e.g.
+/// "killed: memory limit". Shells report death by signal N as 128 + N.
+/// The memory limit is only blamed when the output shows a failed
+/// allocation, since the same signals also come from a plain crash or `kill`.
+pub fn exit_reason(code: i32, limits: Option<&Limits>, output: &[String]) -> String {
+    let limits = limits.copied().unwrap_or_default();
+    let signal = (code > 128).then(|| Signal::try_from(code - 128).ok()).flatten();
+    let out_of_memory = output.iter().any(|line| {
+        let line = line.to_lowercase();
+        OUT_OF_MEMORY_MARKERS.iter().any(|marker| line.contains(marker))
+    });
+
+    match signal {
+        _ if limits.memory.is_some() && out_of_memory => "killed: memory limit".to_string(),
+        Some(Signal::SIGXCPU | Signal::SIGKILL) if limits.cpu_seconds.is_some() => "killed: cpu limit".to_string(),
+        Some(signal) => format!("killed: {}", signal),
+        None => format!("exited with code {}", code),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::configs::yaml_parser::Service;
+
+    #[test]
+    fn test_limits_parse_sizes() {
+        let service: Service = serde_yaml::from_str(
+            "{ name: web, service_type: web, command: serve, dependencies: [], limits: { memory: 512M, open_files: 256, core_dumps: false } }",
+        ).unwrap();
+        let limits = service.limits.unwrap();
+        assert_eq!(limits.memory, Some(512 * 1024 * 1024));
+        assert_eq!(limits.open_files, Some(256));
+        assert!(serde_yaml::from_str::<Limits>("memory: lots").is_err());
+    }
+
+    #[test]
+    fn test_exit_reason() {
+        let memory = Limits { memory: Some(1 << 30), ..Default::default() };
+        let cpu = Limits { cpu_seconds: Some(10), ..Default::default() };
+
+        let both = Limits { memory: Some(1 << 30), cpu_seconds: Some(10), ..Default::default() };
+        let failed_allocation = ["memory allocation of 1048576 bytes failed".to_string()];
+
+        assert_eq!(exit_reason(134, Some(&memory), &failed_allocation), "killed: memory limit");
+        assert_eq!(exit_reason(1, Some(&memory), &["MemoryError".to_string()]), "killed: memory limit");
+        assert_eq!(exit_reason(134, Some(&memory), &[]), "killed: SIGABRT");
+        // A manual `kill -9` is not blamed on the memory limit
+        assert_eq!(exit_reason(137, Some(&memory), &[]), "killed: SIGKILL");
+        assert_eq!(exit_reason(137, Some(&both), &[]), "killed: cpu limit");
+        assert_eq!(exit_reason(152, Some(&cpu), &[]), "killed: cpu limit");
+        assert_eq!(exit_reason(137, None, &[]), "killed: SIGKILL");
+        assert_eq!(exit_reason(3, Some(&memory), &[]), "exited with code 3");
+    }
+
+    #[test]
+    fn test_stopping_a_limited_service_stops_its_command() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let pid_file = dir.path().join("pid");
+        let service: Service = serde_yaml::from_str(&format!(
+            "{{ name: limited, command: 'echo $$ > {}; exec sleep 30', dependencies: [], limits: {{ open_files: 256 }} }}",
+            pid_file.display()
+        )).unwrap();
+        let mut child = crate::process::replicas::spawn(
+            "limits-stop", &service, 0, &Default::default(), &dir.path().to_string_lossy(), None,
+        ).unwrap();
+
+        let deadline = std::time::Instant::now() + std::time::Duration::from_secs(5);
+        let inner = loop {
+            if let Some(pid) = std::fs::read_to_string(&pid_file).ok().and_then(|pid| pid.trim().parse::<u32>().ok()) {
+                break pid;
+            }
+            assert!(std::time::Instant::now() < deadline, "service never started");
+            std::thread::sleep(std::time::Duration::from_millis(20));
+        };
+        assert_ne!(inner, child.id());
+
+        nix::sys::signal::kill(nix::unistd::Pid::from_raw(child.id() as i32), Signal::SIGTERM).unwrap();
+        let status = loop {
+            if let Some(status) = child.try_wait().unwrap() {
+                break status;
+            }
+            if std::time::Instant::now() >= deadline {
+                let _ = child.kill();
+                panic!("wrapper did not exit on SIGTERM");
+            }
+            std::thread::sleep(std::time::Duration::from_millis(20));
+        };
+
+        assert_eq!(status.code(), Some(143));
+        assert!(!crate::process::runs::is_pid_alive(inner), "the command outlived its wrapper");
+        assert_eq!(read_exit("limits-stop", "limited", 0), None);
+    }
+
+    #[test]
+    fn test_limited_service_records_its_exit() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let service: Service = serde_yaml::from_str(
+            "{ name: limited, command: 'exit 3', dependencies: [], limits: { open_files: 256 } }",
+        ).unwrap();
+        let mut child = crate::process::replicas::spawn(
+            "limits-exit", &service, 0, &Default::default(), &dir.path().to_string_lossy(), None,
+        ).unwrap();
+
+        assert_eq!(child.wait().unwrap().code(), Some(3));
+        assert_eq!(read_exit("limits-exit", "limited", 0), Some(3));
+    }
+
+    #[test]
+    fn test_limits_apply_to_child() {
+        let limits = Limits { open_files: Some(64), ..Default::default() };
+        let mut command = std::process::Command::new("sh");
+        command.arg("-c").arg("ulimit -n");
+        unsafe {
+            std::os::unix::process::CommandExt::pre_exec(&mut command, move || apply(&limits));
+        }
+        let output = command.output().unwrap();
+        assert_eq!(String::from_utf8_lossy(&output.stdout).trim(), "64");
+    }
+}
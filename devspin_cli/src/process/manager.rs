@@ -1,5 +1,6 @@
 use crate::process::state::{ProcessInfo};
 use crate::process::global::get_global_state;
+use crate::process::runs::RunRecord;
 
 pub struct ProcessManager; // No state field - stateless
 
@@ -23,7 +24,9 @@ impl ProcessManager {
             .map(|running_process| {
                 ProcessInfo {
                     pid: running_process.info.pid,
+                    container_id: running_process.info.container_id.clone(),
                     service_name: running_process.info.service_name.clone(),
+                    replica: running_process.info.replica,
                     project_name: running_process.info.project_name.clone(),
                     command: running_process.info.command.clone(),
                     start_time: running_process.info.start_time,
@@ -32,6 +35,20 @@ impl ProcessManager {
             })
             .collect();
         
+        // Add services started by earlier invocations that are still alive
+        let mut result = result;
+        for record in RunRecord::all() {
+            for service in record.alive_services() {
+                let known = |known: &ProcessInfo| match &service.container_id {
+                    Some(id) => known.container_id.as_ref() == Some(id),
+                    None => known.pid == service.pid,
+                };
+                if !result.iter().any(known) {
+                    result.push(service.to_process_info(&record.project));
+                }
+            }
+        }
+
         println!("DEBUG: Returning {} services", result.len());
         result
     }
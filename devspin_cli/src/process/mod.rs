@@ -1,5 +1,18 @@
 pub mod state;
 pub mod global;
 pub mod manager;
+pub mod runs;
+pub mod hooks;
+pub mod fingerprint;
+pub mod setup;
+pub mod runner;
+pub mod watch;
+pub mod ports;
+pub mod conflicts;
+pub mod replicas;
+pub mod limits;
+pub mod jobs;
+pub mod health;
+pub mod containers;
 
 pub use state::{ProcessState, ProcessInfo, ProcessStatus};
\ No newline at end of file
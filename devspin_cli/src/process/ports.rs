@@ -0,0 +1,346 @@
+use std::collections::{BTreeMap, BTreeSet};
+use std::path::PathBuf;
+
+use colored::*;
+use serde::{Deserialize, Serialize};
+
+use crate::configs::env::ResolvedEnv;
+use crate::configs::paths::{file_stem, ports_dir};
+use crate::configs::yaml_parser::{PortSpec, ProjectConfig, Service};
+use crate::error::{Result, ToolError};
+use crate::process::runs::RunRecord;
+
+/// Range `port: auto` picks from; clear of the usual 3000/5000/8000 defaults.
+const AUTO_PORT_RANGE: std::ops::RangeInclusive<u16> = 4000..=4999;
+
+/// Ports assigned to one project's services, kept under the devspin home so
+/// `port: auto` stays stable across restarts and other projects can avoid them.
+#[derive(Debug, Default, Deserialize, Serialize)]
+pub struct PortStore {
+    #[serde(skip)]
+    project: String,
+    /// `service` or `service.name` to the assigned port; replicas after
+    /// the first add `#<index>` to the key
+    ports: BTreeMap<String, u16>,
+}
+
+impl PortStore {
+    fn path(project: &str) -> PathBuf {
+        ports_dir().join(format!("{}.json", file_stem(project)))
+    }
+
+    pub fn load(project: &str) -> Result<Self> {
+        let path = Self::path(project);
+        let mut store: Self = if path.exists() {
+            let content = std::fs::read_to_string(&path)?;
+            serde_json::from_str(&content).map_err(|e| ToolError::ParseError(format!(
+                "Invalid port file {}: {}", path.display(), e
+            )))?
+        } else {
+            Self::default()
+        };
+        store.project = project.to_string();
+        Ok(store)
+    }
+
+    pub fn save(&self) -> Result<()> {
+        std::fs::create_dir_all(ports_dir())?;
+        let content = serde_json::to_string_pretty(self)
+            .map_err(|e| ToolError::GenericError(e.to_string()))?;
+        std::fs::write(Self::path(&self.project), content)?;
+        Ok(())
+    }
+
+    /// Port to project for every other project's assignments, skipping unreadable files.
+    fn claimed_elsewhere(project: &str) -> BTreeMap<u16, String> {
+        let Ok(entries) = std::fs::read_dir(ports_dir()) else {
+            return BTreeMap::new();
+        };
+
+        let mut claimed = BTreeMap::new();
+        for path in entries.filter_map(|entry| entry.ok()).map(|entry| entry.path()) {
+            let Some(other) = path.file_stem().map(|stem| stem.to_string_lossy().to_string()) else { continue };
+            if other == file_stem(project) {
+                continue;
+            }
+            if let Ok(store) = Self::load(&other) {
+                claimed.extend(store.ports.values().map(|port| (*port, other.clone())));
+            }
+        }
+        claimed
+    }
+}
+
+/// One declared port: its store key and the variable it's exposed as.
+struct PortDecl {
+    key: String,
+    label: String,
+    var: String,
+    spec: PortSpec,
+    /// Consecutive ports reserved, one per replica
+    count: u16,
+}
+
+impl PortDecl {
+    fn keys(&self) -> impl Iterator<Item = String> + '_ {
+        (0..self.count).map(|replica| match replica {
+            0 => self.key.clone(),
+            replica => format!("{}#{}", self.key, replica),
+        })
+    }
+}
+
+fn declared_ports(service: &Service) -> Vec<PortDecl> {
+    let prefix = env_name(&service.name);
+    let count = u16::try_from(service.replicas.max(1)).unwrap_or(u16::MAX);
+    let main = service.port.map(|spec| PortDecl {
+        key: service.name.clone(),
+        label: service.name.clone(),
+        var: format!("{}_PORT", prefix),
+        spec,
+        count,
+    });
+    let named = service.ports.iter().map(|(name, spec)| PortDecl {
+        key: format!("{}.{}", service.name, name),
+        label: format!("{} ({})", service.name, name),
+        var: format!("{}_{}_PORT", prefix, env_name(name)),
+        spec: *spec,
+        count,
+    });
+    main.into_iter().chain(named).collect()
+}
+
+/// `api-server` becomes `API_SERVER`.
+pub fn env_name(name: &str) -> String {
+    name.chars()
+        .map(|c| if c.is_ascii_alphanumeric() { c.to_ascii_uppercase() } else { '_' })
+        .collect()
+}
+
+/// Picks a port for every declared port, preferring the previous assignment.
+/// Replicated services get a block of consecutive ports starting there.
+/// Returns the variable each port is exposed as, plus warnings for fixed
+/// ports that collide with another project's. Ports bound right now are
+/// reported by the pre-flight check in `start` instead.
+///
+/// With `trust_stored`, a port keeps its previous assignment without the
+/// `is_free` probe (its own service is the one holding it); new picks are
+/// always probed. A block that has to grow (a service scaled past its
+/// reservation) into ports held by others moves to a free range instead;
+/// replicas already running keep their ports until they restart.
+fn allocate(
+    services: &[Service],
+    store: &mut PortStore,
+    claimed: &BTreeMap<u16, String>,
+    trust_stored: bool,
+    is_free: impl Fn(u16) -> bool,
+) -> Result<(BTreeMap<String, u16>, Vec<String>)> {
+    let declared: Vec<PortDecl> = services.iter().flat_map(declared_ports).collect();
+    let mut assigned: BTreeMap<String, u16> = BTreeMap::new();
+    let mut vars = BTreeMap::new();
+    let mut warnings = Vec::new();
+
+    // Fixed ports first so `auto` never hands one of them out
+    for decl in &declared {
+        let PortSpec::Fixed(port) = decl.spec else { continue };
+        for (key, port) in decl.keys().zip(port..) {
+            if let Some(other) = claimed.get(&port) {
+                warnings.push(format!("Port {} of {} is also used by project '{}'", port, decl.label, other));
+            }
+            assigned.insert(key, port);
+        }
+        vars.insert(decl.var.clone(), port);
+    }
+
+    for decl in &declared {
+        let PortSpec::Auto(_) = decl.spec else { continue };
+        let usable = |start: u16, assigned: &BTreeMap<String, u16>, trusted: &BTreeSet<u16>| {
+            (0..decl.count).all(|offset| {
+                let Some(port) = start.checked_add(offset) else { return false };
+                !claimed.contains_key(&port)
+                    && !assigned.values().any(|used| *used == port)
+                    && (trusted.contains(&port) || is_free(port))
+            })
+        };
+
+        let own: BTreeSet<u16> = if trust_stored {
+            decl.keys().filter_map(|key| store.ports.get(&key).copied()).collect()
+        } else {
+            BTreeSet::new()
+        };
+        let previous = store.ports.get(&decl.key).copied().filter(|port| usable(*port, &assigned, &own));
+        let port = match previous {
+            Some(port) => port,
+            None => AUTO_PORT_RANGE
+                .clone()
+                .find(|port| usable(*port, &assigned, &BTreeSet::new()))
+                .ok_or_else(|| ToolError::ProcessError(format!(
+                    "No free port left in {}-{} for {}",
+                    AUTO_PORT_RANGE.start(), AUTO_PORT_RANGE.end(), decl.label
+                )))?,
+        };
+        for (key, port) in decl.keys().zip(port..) {
+            assigned.insert(key, port);
+        }
+        vars.insert(decl.var.clone(), port);
+    }
+
+    store.ports = assigned;
+    Ok((vars, warnings))
+}
+
+/// Assigns the project's ports, saves them, and exposes each one as
+/// `<SERVICE>_PORT` / `<SERVICE>_<NAME>_PORT` on the project environment.
+///
+/// While the project is running its ports are in use by its own services,
+/// so the stored assignments are reused without checking them; any port
+/// picked anew is still checked.
+pub fn assign_ports(project: &ProjectConfig, env: &mut ResolvedEnv) -> Result<()> {
+    let services = project.services.as_deref().unwrap_or_default();
+    if services.iter().all(|service| service.port.is_none() && service.ports.is_empty()) {
+        return Ok(());
+    }
+
+    let running = RunRecord::load(&project.name)?.is_some_and(|record| record.is_running());
+    let mut store = PortStore::load(&project.name)?;
+    let claimed = PortStore::claimed_elsewhere(&project.name);
+    let (vars, warnings) = allocate(services, &mut store, &claimed, running, port_check::is_local_port_free)?;
+
+    for warning in warnings {
+        eprintln!("{} {}", "WARN:".yellow(), warning.yellow());
+    }
+    store.save()?;
+
+    for (var, port) in vars {
+        env.set_default(&var, port.to_string());
+    }
+    Ok(())
+}
+
+/// Exposes a service's own ports as `PORT` and `<NAME>_PORT`, taken from
+/// the project-wide variables set by [`assign_ports`]. These replace any
+/// `PORT` inherited from the project environment or `.env`.
+pub fn inject_service_ports(service: &Service, env: &mut ResolvedEnv) {
+    let prefix = env_name(&service.name);
+    let mut own = Vec::new();
+    if service.port.is_some() {
+        own.push(("PORT".to_string(), format!("{}_PORT", prefix)));
+    }
+    for name in service.ports.keys() {
+        let name = env_name(name);
+        own.push((format!("{}_PORT", name), format!("{}_{}_PORT", prefix, name)));
+    }
+
+    for (var, source) in own {
+        if let Some(port) = env.vars.get(&source).cloned() {
+            env.insert(&var, port);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn services() -> Vec<Service> {
+        serde_yaml::from_str(r#"
+- { name: api, service_type: api, command: api, dependencies: [], port: auto, ports: { metrics: auto } }
+- { name: web-ui, service_type: web, command: web, dependencies: [], port: 3000 }
+"#).unwrap()
+    }
+
+    #[test]
+    fn test_auto_ports_are_stable_and_avoid_conflicts() {
+        let services = services();
+        let mut store = PortStore::default();
+        let claimed = BTreeMap::from([(4000, "other".to_string()), (3000, "shop".to_string())]);
+
+        let (vars, warnings) = allocate(&services, &mut store, &claimed, false, |port| port != 4001).unwrap();
+        assert_eq!(vars["API_PORT"], 4002);
+        assert_eq!(vars["API_METRICS_PORT"], 4003);
+        assert_eq!(vars["WEB_UI_PORT"], 3000);
+        assert_eq!(warnings, vec!["Port 3000 of web-ui is also used by project 'shop'"]);
+
+        // Previous assignments win over lower free ports
+        let (again, _) = allocate(&services, &mut store, &BTreeMap::new(), false, |_| true).unwrap();
+        assert_eq!(again, vars);
+
+        // A taken port is replaced
+        let (moved, _) = allocate(&services, &mut store, &BTreeMap::new(), false, |port| port != 4002).unwrap();
+        assert_eq!(moved["API_PORT"], 4000);
+    }
+
+    #[test]
+    fn test_running_project_only_trusts_its_stored_ports() {
+        let mut services = services();
+        let mut store = PortStore::default();
+        allocate(&services, &mut store, &BTreeMap::new(), false, |_| true).unwrap();
+        assert_eq!(store.ports["api"], 4000);
+
+        // Running: api still holds 4000 and 4001, and something else took 4002
+        services.push(serde_yaml::from_str("{ name: cache, command: cache, dependencies: [], port: auto }").unwrap());
+        let (vars, _) = allocate(&services, &mut store, &BTreeMap::new(), true, |port| port > 4002).unwrap();
+        assert_eq!(vars["API_PORT"], 4000);
+        assert_eq!(vars["API_METRICS_PORT"], 4001);
+        assert_eq!(vars["CACHE_PORT"], 4003);
+    }
+
+    #[test]
+    fn test_scaled_block_moves_when_it_cannot_grow() {
+        let mut services: Vec<Service> = serde_yaml::from_str(r#"
+- { name: worker, command: work, dependencies: [], port: auto }
+- { name: api, command: api, dependencies: [], port: auto }
+"#).unwrap();
+        let mut store = PortStore::default();
+        allocate(&services, &mut store, &BTreeMap::new(), false, |_| true).unwrap();
+        assert_eq!((store.ports["worker"], store.ports["api"]), (4000, 4001));
+
+        // Scaling worker to 2 while running: api keeps 4001, worker gets a new block
+        services[0].replicas = 2;
+        let (vars, _) = allocate(&services, &mut store, &BTreeMap::new(), true, |port| port > 4001).unwrap();
+        assert_eq!(vars["WORKER_PORT"], 4002);
+        assert_eq!(store.ports["worker#1"], 4003);
+        assert_eq!(vars["API_PORT"], 4001);
+    }
+
+    #[test]
+    fn test_replicas_reserve_consecutive_ports() {
+        let services: Vec<Service> = serde_yaml::from_str(r#"
+- { name: worker, service_type: worker, command: work, dependencies: [], port: auto, replicas: 3 }
+- { name: api, service_type: api, command: api, dependencies: [], port: auto }
+"#).unwrap();
+        let mut store = PortStore::default();
+
+        let (vars, _) = allocate(&services, &mut store, &BTreeMap::new(), false, |port| port != 4001).unwrap();
+        assert_eq!(vars["WORKER_PORT"], 4002);
+        assert_eq!(vars["API_PORT"], 4000);
+        assert_eq!(store.ports["worker#2"], 4004);
+    }
+
+    #[test]
+    fn test_own_ports_are_not_claimed_elsewhere() {
+        for (project, port) in [("Port Shop", 4990), ("Port Depot", 4991)] {
+            let mut store = PortStore::load(project).unwrap();
+            store.ports.insert("api".to_string(), port);
+            store.save().unwrap();
+        }
+
+        let claimed = PortStore::claimed_elsewhere("Port Shop");
+        assert!(!claimed.contains_key(&4990));
+        assert_eq!(claimed[&4991], "Port-Depot");
+    }
+
+    #[test]
+    fn test_service_sees_its_own_ports() {
+        let services = services();
+        let mut env = ResolvedEnv::default();
+        env.set_default("API_PORT", "4100".to_string());
+        env.set_default("API_METRICS_PORT", "4101".to_string());
+
+        env.insert("PORT", "8080".to_string());
+
+        inject_service_ports(&services[0], &mut env);
+        assert_eq!(env.vars["PORT"], "4100");
+        assert_eq!(env.vars["METRICS_PORT"], "4101");
+    }
+}
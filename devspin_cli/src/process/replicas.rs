@@ -0,0 +1,168 @@
+use std::fs::OpenOptions;
+use std::path::{Path, PathBuf};
+use std::os::unix::process::CommandExt;
+use std::process::{Child, Command, Stdio};
+use std::time::{Duration, Instant};
+
+use nix::sys::signal::{kill, Signal};
+use nix::unistd::Pid;
+
+use crate::configs::env::ResolvedEnv;
+use crate::configs::paths::{file_stem, logs_dir};
+use crate::configs::yaml_parser::Service;
+use crate::error::Result;
+use crate::process::limits;
+use crate::process::ports::env_name;
+use crate::process::runs::is_pid_alive;
+
+/// Environment for one replica: `DEVSPIN_REPLICA` plus the service's own
+/// port variables shifted by the replica index, so replica 2 of an API on
+/// 4000 listens on 4002.
+pub fn replica_env(service: &Service, env: &ResolvedEnv, replica: u32) -> ResolvedEnv {
+    let mut env = env.clone();
+    env.insert("DEVSPIN_REPLICA", replica.to_string());
+    if replica == 0 {
+        return env;
+    }
+
+    let mut own: Vec<String> = service.ports.keys().map(|name| format!("{}_PORT", env_name(name))).collect();
+    if service.port.is_some() {
+        own.push("PORT".to_string());
+    }
+    for var in own {
+        let shifted = env.vars.get(&var).and_then(|port| port.parse::<u32>().ok()).map(|port| port + replica);
+        if let Some(port) = shifted {
+            env.insert(&var, port.to_string());
+        }
+    }
+    env
+}
+
+/// `api` for a single instance, `api#2` for a replica.
+pub fn replica_label(service: &str, replica: u32, replicas: u32) -> String {
+    if replicas > 1 || replica > 0 {
+        format!("{}#{}", service, replica)
+    } else {
+        service.to_string()
+    }
+}
+
+/// Log file a replica's stdout and stderr are appended to.
+pub fn log_path(project: &str, service: &str, replica: u32) -> PathBuf {
+    logs_dir().join(file_stem(project)).join(format!("{}.{}.log", file_stem(service), replica))
+}
+
+/// Spawns one replica of a service under `sh -c`. With `log`, output goes
+/// to that file instead of the terminal so replicas don't interleave.
+/// Services with `limits` get them applied before exec and have their exit
+/// code recorded (see `limits::EXIT_WRAPPER`).
+pub fn spawn(
+    project: &str,
+    service: &Service,
+    replica: u32,
+    env: &ResolvedEnv,
+    working_dir: &str,
+    log: Option<&Path>,
+) -> Result<Child> {
+    let mut command = Command::new("sh");
+    command.current_dir(working_dir);
+    command.envs(&env.vars);
+    // Lets a process outliving its run be traced back to it (see `conflicts`)
+    command.env("DEVSPIN_PROJECT", project).env("DEVSPIN_SERVICE", &service.name);
+
+    match &service.limits {
+        Some(service_limits) => {
+            let exit_file = limits::exit_file(project, &service.name, replica);
+            if let Some(dir) = exit_file.parent() {
+                std::fs::create_dir_all(dir)?;
+            }
+            let _ = std::fs::remove_file(&exit_file);
+
+            command.arg("-c").arg(limits::EXIT_WRAPPER).arg("devspin").arg(&service.command).arg(&exit_file);
+            let service_limits = *service_limits;
+            // SAFETY: `limits::apply` only calls getrlimit/setrlimit, which are
+            // async-signal-safe, and allocates nothing
+            unsafe {
+                command.pre_exec(move || limits::apply(&service_limits));
+            }
+        }
+        None => {
+            command.arg("-c").arg(&service.command);
+        }
+    }
+
+    if let Some(log) = log {
+        if let Some(dir) = log.parent() {
+            std::fs::create_dir_all(dir)?;
+        }
+        let file = OpenOptions::new().create(true).append(true).open(log)?;
+        command.stdout(Stdio::from(file.try_clone()?)).stderr(Stdio::from(file));
+    }
+
+    Ok(command.spawn()?)
+}
+
+/// Sends SIGTERM to a process started by an earlier invocation and waits up
+/// to `timeout` for it to exit before sending SIGKILL.
+pub async fn terminate(pid: u32, timeout: Duration) {
+    let _ = kill(Pid::from_raw(pid as i32), Signal::SIGTERM);
+    let deadline = Instant::now() + timeout;
+    while is_pid_alive(pid) {
+        if Instant::now() >= deadline {
+            let _ = kill(Pid::from_raw(pid as i32), Signal::SIGKILL);
+            return;
+        }
+        tokio::time::sleep(Duration::from_millis(100)).await;
+    }
+}
+
+/// The last `count` lines of a log file, or nothing if it doesn't exist.
+pub fn tail_lines(path: &Path, count: usize) -> Vec<String> {
+    let Ok(content) = std::fs::read_to_string(path) else {
+        return Vec::new();
+    };
+    let lines: Vec<&str> = content.lines().collect();
+    lines[lines.len().saturating_sub(count)..].iter().map(|line| line.to_string()).collect()
+}
+
+/// Parses a `service=N` argument of `devspin scale`.
+pub fn parse_scale(input: &str) -> std::result::Result<(String, u32), String> {
+    let (service, count) = input
+        .split_once('=')
+        .ok_or_else(|| format!("expected SERVICE=N, got '{}'", input))?;
+    let count = count
+        .trim()
+        .parse()
+        .map_err(|_| format!("invalid replica count '{}'", count))?;
+    Ok((service.trim().to_string(), count))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_replica_ports_are_offset() {
+        let service: Service = serde_yaml::from_str(
+            "{ name: worker, service_type: worker, command: work, dependencies: [], port: 4000, ports: { metrics: 9100 }, replicas: 3 }",
+        ).unwrap();
+        let mut env = ResolvedEnv::default();
+        env.insert("PORT", "4000".to_string());
+        env.insert("METRICS_PORT", "9100".to_string());
+        env.insert("DB_PORT", "5432".to_string());
+
+        let second = replica_env(&service, &env, 2);
+        assert_eq!(second.vars["DEVSPIN_REPLICA"], "2");
+        assert_eq!(second.vars["PORT"], "4002");
+        assert_eq!(second.vars["METRICS_PORT"], "9102");
+        assert_eq!(second.vars["DB_PORT"], "5432");
+        assert_eq!(replica_env(&service, &env, 0).vars["PORT"], "4000");
+    }
+
+    #[test]
+    fn test_parse_scale() {
+        assert_eq!(parse_scale("worker=4").unwrap(), ("worker".to_string(), 4));
+        assert!(parse_scale("worker").is_err());
+        assert!(parse_scale("worker=-1").is_err());
+    }
+}
@@ -0,0 +1,73 @@
+use colored::*;
+
+use crate::configs::env::{resolve_task_env, ResolvedEnv};
+use crate::configs::yaml_parser::{ProjectConfig, Task};
+use crate::error::{Result, ToolError};
+use crate::process::fingerprint::{fingerprint, FingerprintStore};
+
+/// Runs the named tasks in the given order (see `configs::tasks::task_order`),
+/// skipping incremental tasks whose fingerprints are unchanged unless `force`.
+pub async fn run_tasks(
+    project: &ProjectConfig,
+    order: &[&str],
+    project_env: &ResolvedEnv,
+    overrides: &[(String, String)],
+    force: bool,
+) -> Result<()> {
+    let mut fingerprints = FingerprintStore::load(&project.name)?;
+    for name in order {
+        run_task(project, name, &project.commands[*name], project_env, overrides, &mut fingerprints, force).await?;
+    }
+    Ok(())
+}
+
+async fn run_task(
+    project: &ProjectConfig,
+    name: &str,
+    task: &Task,
+    project_env: &ResolvedEnv,
+    overrides: &[(String, String)],
+    fingerprints: &mut FingerprintStore,
+    force: bool,
+) -> Result<()> {
+    let Some(command) = &task.command else {
+        return Ok(());
+    };
+
+    let working_dir = project.resolve_path(task.working_dir.as_deref().unwrap_or(""));
+    if !force {
+        if let Some(current) = fingerprint(&working_dir, task)? {
+            if fingerprints.get(name) == Some(&current) {
+                println!("{} {}", "✓".green(), format!("Task {} is up to date", name).dimmed());
+                return Ok(());
+            }
+        }
+    }
+
+    let env = resolve_task_env(project, task, project_env, overrides)?;
+
+    println!("{} {}", "▶".cyan(), format!("Running task: {}", name).bold());
+    println!("  {}", env.redactor().redact(command).dimmed());
+
+    let status = tokio::process::Command::new("sh")
+        .arg("-c")
+        .arg(command)
+        .current_dir(&working_dir)
+        .envs(&env.vars)
+        .env("DEVSPIN_PROJECT", &project.name)
+        .env("DEVSPIN_TASK", name)
+        .status()
+        .await
+        .map_err(|e| ToolError::ProcessError(format!("Failed to run task '{}': {}", name, e)))?;
+
+    if !status.success() {
+        return Err(ToolError::ProcessError(format!("Task '{}' failed ({})", name, status)));
+    }
+
+    // Fingerprint after the run so the outputs it just wrote are included
+    if let Some(done) = fingerprint(&working_dir, task)? {
+        fingerprints.set(name, done);
+        fingerprints.save()?;
+    }
+    Ok(())
+}
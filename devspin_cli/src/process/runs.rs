@@ -0,0 +1,438 @@
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use nix::errno::Errno;
+use nix::sys::signal::kill;
+use nix::unistd::Pid;
+use serde::{Deserialize, Serialize};
+use sysinfo::{ProcessRefreshKind, System};
+
+use crate::configs::paths::{file_stem, runs_dir};
+use crate::error::{Result, ToolError};
+use crate::process::containers;
+use crate::process::state::{ProcessInfo, ProcessStatus};
+
+/// On-disk record of the services a project has running.
+///
+/// The in-memory `ProcessState` only lives as long as one CLI invocation;
+/// this record is what lets a later `status`, `stop` or `list` find the
+/// processes a previous `start` spawned.
+#[derive(Debug, Clone, Default, Deserialize, Serialize)]
+pub struct RunRecord {
+    pub project: String,
+    pub config_path: Option<PathBuf>,
+    pub services: Vec<ServiceRecord>,
+    /// Oneshot services run during this run, with how they ended
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub jobs: Vec<JobRecord>,
+    /// External services this run waited for; devspin never signals these
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub externals: Vec<String>,
+    /// What `start` was given besides the config
+    #[serde(default)]
+    pub inputs: StartInputs,
+}
+
+/// The command-line inputs a run was started with, so commands acting on it
+/// later (stop hooks, scaling) load the same config and environment.
+#[derive(Debug, Clone, Default, PartialEq, Deserialize, Serialize)]
+pub struct StartInputs {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub profile: Option<String>,
+    /// The `--env` file, made absolute
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub env_file: Option<PathBuf>,
+    /// `--set` overrides, in command-line order
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub overrides: Vec<(String, String)>,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct ServiceRecord {
+    pub service_name: String,
+    #[serde(default)]
+    pub replica: u32,
+    /// 0 for containers, which are tracked by `container_id` instead
+    pub pid: u32,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub container_id: Option<String>,
+    pub command: String,
+    /// Seconds since the Unix epoch
+    pub started_at: u64,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct JobRecord {
+    pub service_name: String,
+    /// Seconds since the Unix epoch
+    pub started_at: u64,
+    pub duration_ms: u64,
+    pub exit_code: i32,
+}
+
+impl RunRecord {
+    pub fn path(project: &str) -> PathBuf {
+        runs_dir().join(format!("{}.json", file_stem(project)))
+    }
+
+    pub fn load(project: &str) -> Result<Option<Self>> {
+        let path = Self::path(project);
+        if !path.exists() {
+            return Ok(None);
+        }
+        Self::load_from(&path).map(Some)
+    }
+
+    fn load_from(path: &Path) -> Result<Self> {
+        let content = std::fs::read_to_string(path)?;
+        serde_json::from_str(&content).map_err(|e| ToolError::ParseError(format!(
+            "Invalid run record {}: {}", path.display(), e
+        )))
+    }
+
+    /// Every run record on disk, skipping unreadable ones.
+    pub fn all() -> Vec<Self> {
+        let Ok(entries) = std::fs::read_dir(runs_dir()) else {
+            return Vec::new();
+        };
+
+        entries
+            .flatten()
+            .filter(|entry| entry.path().extension().is_some_and(|ext| ext == "json"))
+            .filter_map(|entry| Self::load_from(&entry.path()).ok())
+            .collect()
+    }
+
+    /// Writes the record, or deletes it once nothing is left in it.
+    pub fn save(&self) -> Result<()> {
+        let path = Self::path(&self.project);
+
+        if self.services.is_empty() && self.jobs.is_empty() && self.externals.is_empty() {
+            if path.exists() {
+                std::fs::remove_file(&path)?;
+            }
+            return Ok(());
+        }
+
+        std::fs::create_dir_all(runs_dir())?;
+        let content = serde_json::to_string_pretty(self)
+            .map_err(|e| ToolError::GenericError(e.to_string()))?;
+        std::fs::write(path, content)?;
+        Ok(())
+    }
+
+    /// Adds a freshly spawned service to the project's record.
+    pub fn record_service(
+        project: &str,
+        config_path: Option<&Path>,
+        service_name: &str,
+        replica: u32,
+        pid: u32,
+        command: &str,
+    ) -> Result<()> {
+        Self::add_service(project, config_path, ServiceRecord::new(service_name, replica, pid, None, command))
+    }
+
+    /// Adds a freshly started container to the project's record.
+    pub fn record_container(
+        project: &str,
+        config_path: Option<&Path>,
+        service_name: &str,
+        replica: u32,
+        container_id: &str,
+        image: &str,
+    ) -> Result<()> {
+        let service = ServiceRecord::new(service_name, replica, 0, Some(container_id.to_string()), image);
+        Self::add_service(project, config_path, service)
+    }
+
+    fn add_service(project: &str, config_path: Option<&Path>, service: ServiceRecord) -> Result<()> {
+        let mut record = Self::load(project)?.unwrap_or_else(|| RunRecord {
+            project: project.to_string(),
+            ..Default::default()
+        });
+
+        if config_path.is_some() {
+            record.config_path = config_path.map(Path::to_path_buf);
+        }
+        // Drop entries whose process is gone so restarts don't pile up
+        record.services.retain(|existing| existing.is_alive() && !existing.same_as(&service));
+        record.services.push(service);
+
+        record.save()
+    }
+
+    /// Records how a oneshot service ended, replacing any earlier run of it.
+    pub fn record_job(project: &str, config_path: Option<&Path>, job: JobRecord) -> Result<()> {
+        let mut record = Self::load(project)?.unwrap_or_else(|| RunRecord {
+            project: project.to_string(),
+            ..Default::default()
+        });
+
+        if config_path.is_some() {
+            record.config_path = config_path.map(Path::to_path_buf);
+        }
+        record.jobs.retain(|existing| existing.service_name != job.service_name);
+        record.jobs.push(job);
+        record.save()
+    }
+
+    /// Notes that this run depends on an external service.
+    pub fn record_external(project: &str, config_path: Option<&Path>, service_name: &str) -> Result<()> {
+        let mut record = Self::load(project)?.unwrap_or_else(|| RunRecord {
+            project: project.to_string(),
+            ..Default::default()
+        });
+
+        if config_path.is_some() {
+            record.config_path = config_path.map(Path::to_path_buf);
+        }
+        if !record.externals.iter().any(|name| name == service_name) {
+            record.externals.push(service_name.to_string());
+        }
+        record.save()
+    }
+
+    /// Stores what the run was started with. Called once the run has
+    /// something recorded; a run that started nothing has nothing to act on.
+    pub fn record_inputs(project: &str, inputs: StartInputs) -> Result<()> {
+        if let Some(mut record) = Self::load(project)? {
+            record.inputs = inputs;
+            record.save()?;
+        }
+        Ok(())
+    }
+
+    /// Removes a stopped process from the project's record. Once the last
+    /// one is gone the run is over, and its job results go with it.
+    pub fn forget_pid(project: &str, pid: u32) -> Result<()> {
+        if let Some(mut record) = Self::load(project)? {
+            record.services.retain(|service| service.pid != pid || service.container_id.is_some());
+            if record.services.is_empty() {
+                record.end();
+            }
+            record.save()?;
+        }
+        Ok(())
+    }
+
+    /// Removes a stopped container from the project's record.
+    pub fn forget_container(project: &str, container_id: &str) -> Result<()> {
+        if let Some(mut record) = Self::load(project)? {
+            record.services.retain(|service| service.container_id.as_deref() != Some(container_id));
+            if record.services.is_empty() {
+                record.end();
+            }
+            record.save()?;
+        }
+        Ok(())
+    }
+
+    /// Drops the record of a project with no processes left, e.g. one that
+    /// only ran jobs or waited for external services.
+    pub fn discard_if_idle(project: &str) -> Result<()> {
+        if let Some(mut record) = Self::load(project)? {
+            if !record.is_running() {
+                record.services.clear();
+                record.end();
+                record.save()?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Forgets what the run did besides its processes.
+    fn end(&mut self) {
+        self.jobs.clear();
+        self.externals.clear();
+        self.inputs = StartInputs::default();
+    }
+
+    /// Services whose process or container is still alive.
+    pub fn alive_services(&self) -> Vec<&ServiceRecord> {
+        self.services.iter().filter(|service| service.is_alive()).collect()
+    }
+
+    /// The last recorded run of a oneshot service.
+    pub fn job(&self, service_name: &str) -> Option<&JobRecord> {
+        self.jobs.iter().find(|job| job.service_name == service_name)
+    }
+
+    pub fn is_running(&self) -> bool {
+        !self.alive_services().is_empty()
+    }
+}
+
+impl StartInputs {
+    /// The `--env` file as `resolve_project_env` takes it.
+    pub fn env_files(&self) -> Vec<String> {
+        self.env_file.iter().map(|path| path.to_string_lossy().to_string()).collect()
+    }
+}
+
+impl ServiceRecord {
+    fn new(service_name: &str, replica: u32, pid: u32, container_id: Option<String>, command: &str) -> Self {
+        ServiceRecord {
+            service_name: service_name.to_string(),
+            replica,
+            pid,
+            container_id,
+            command: command.to_string(),
+            started_at: SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .map(|d| d.as_secs())
+                .unwrap_or_default(),
+        }
+    }
+
+    pub fn is_alive(&self) -> bool {
+        match &self.container_id {
+            Some(id) => containers::is_running(id),
+            None => is_pid_alive(self.pid) && !started_after(self.pid, self.started_at),
+        }
+    }
+
+    /// Whether both records track the same process or container.
+    fn same_as(&self, other: &ServiceRecord) -> bool {
+        match (&self.container_id, &other.container_id) {
+            (None, None) => self.pid == other.pid,
+            (Some(a), Some(b)) => a == b,
+            _ => false,
+        }
+    }
+
+    pub fn to_process_info(&self, project: &str) -> ProcessInfo {
+        ProcessInfo {
+            pid: self.pid,
+            container_id: self.container_id.clone(),
+            service_name: self.service_name.clone(),
+            replica: self.replica,
+            project_name: project.to_string(),
+            command: self.command.clone(),
+            start_time: UNIX_EPOCH + Duration::from_secs(self.started_at),
+            status: ProcessStatus::Running,
+        }
+    }
+}
+
+impl JobRecord {
+    pub fn succeeded(&self) -> bool {
+        self.exit_code == 0
+    }
+
+    pub fn duration(&self) -> Duration {
+        Duration::from_millis(self.duration_ms)
+    }
+}
+
+/// Whether a process with this PID exists (signal 0 probe).
+/// Whether `pid` belongs to a process started after `started_at`, which
+/// means the PID was reused (after a reboot, say) and the recorded process
+/// is gone. Start times are only precise to the second, hence the slack.
+fn started_after(pid: u32, started_at: u64) -> bool {
+    const SLACK_SECS: u64 = 2;
+    let mut system = System::new();
+    let pid = sysinfo::Pid::from_u32(pid);
+    if !system.refresh_process_specifics(pid, ProcessRefreshKind::new()) {
+        return false;
+    }
+    system.process(pid).is_some_and(|process| process.start_time() > started_at + SLACK_SECS)
+}
+
+pub fn is_pid_alive(pid: u32) -> bool {
+    // PID 0 would probe our own process group
+    if pid == 0 {
+        return false;
+    }
+    // EPERM still means the process exists, it just isn't ours to signal
+    matches!(kill(Pid::from_raw(pid as i32), None), Ok(()) | Err(Errno::EPERM))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A PID that is certainly gone: a child that has already been reaped.
+    fn dead_pid() -> u32 {
+        let mut child = std::process::Command::new("true").spawn().unwrap();
+        let pid = child.id();
+        child.wait().unwrap();
+        pid
+    }
+
+    #[test]
+    fn test_path_stays_in_runs_dir() {
+        for name in ["../../etc/passwd", "a/b", "..", "My Shop"] {
+            let path = RunRecord::path(name);
+            assert_eq!(path.parent(), Some(runs_dir().as_path()), "{}", name);
+        }
+        assert_eq!(RunRecord::path("My Shop"), runs_dir().join("My-Shop.json"));
+    }
+
+    #[test]
+    fn test_save_load_and_prune() {
+        let project = "runs-save-load";
+        let config = Path::new("/srv/shop/devspin.yaml");
+        let me = std::process::id();
+
+        RunRecord::record_service(project, Some(config), "web", 0, dead_pid(), "npm start").unwrap();
+        RunRecord::record_service(project, None, "web", 1, me, "npm start").unwrap();
+        RunRecord::record_external(project, None, "billing").unwrap();
+
+        let record = RunRecord::load(project).unwrap().unwrap();
+        assert_eq!(record.config_path.as_deref(), Some(config));
+        // The dead replica was dropped when the live one was added
+        assert_eq!(record.services.len(), 1);
+        assert_eq!((record.services[0].replica, record.services[0].pid), (1, me));
+        assert_eq!(record.externals, vec!["billing"]);
+        assert!(record.is_running());
+        assert!(RunRecord::all().iter().any(|record| record.project == project));
+
+        RunRecord::forget_pid(project, me).unwrap();
+        assert!(RunRecord::load(project).unwrap().is_none());
+        assert!(!RunRecord::path(project).exists());
+    }
+
+    #[test]
+    fn test_inputs_survive_until_the_run_ends() {
+        let project = "runs-inputs";
+        let me = std::process::id();
+        let inputs = StartInputs {
+            profile: Some("dev".to_string()),
+            env_file: Some(PathBuf::from("/srv/shop/.env.dev")),
+            overrides: vec![("LOG_LEVEL".to_string(), "debug".to_string())],
+        };
+
+        RunRecord::record_inputs(project, inputs.clone()).unwrap();
+        assert!(RunRecord::load(project).unwrap().is_none());
+
+        RunRecord::record_service(project, None, "web", 0, me, "npm start").unwrap();
+        RunRecord::record_inputs(project, inputs.clone()).unwrap();
+        RunRecord::record_service(project, None, "worker", 0, me + 1, "npm run worker").unwrap();
+        assert_eq!(RunRecord::load(project).unwrap().unwrap().inputs, inputs);
+
+        RunRecord::forget_pid(project, me).unwrap();
+        RunRecord::forget_pid(project, me + 1).unwrap();
+        assert!(RunRecord::load(project).unwrap().is_none());
+    }
+
+    #[test]
+    fn test_reused_pid_is_not_alive() {
+        let mut service = ServiceRecord::new("web", 0, std::process::id(), None, "npm start");
+        assert!(service.is_alive());
+
+        // Recorded well before this process started: the PID was reused
+        service.started_at = 1;
+        assert!(!service.is_alive());
+    }
+
+    #[test]
+    fn test_discard_if_idle() {
+        let project = "runs-idle";
+        RunRecord::record_external(project, None, "billing").unwrap();
+        assert!(RunRecord::load(project).unwrap().is_some());
+
+        RunRecord::discard_if_idle(project).unwrap();
+        assert!(RunRecord::load(project).unwrap().is_none());
+    }
+}
@@ -0,0 +1,131 @@
+use colored::*;
+
+use crate::configs::env::ResolvedEnv;
+use crate::configs::yaml_parser::{ProjectConfig, Service, Setup};
+use crate::error::{Result, ToolError};
+use crate::process::fingerprint::{hash_globs, FingerprintStore};
+
+/// Lockfiles watched when a setup step doesn't list its own `watch` files,
+/// at the top of the service directory only.
+const DEFAULT_WATCH_FILES: [&str; 9] = [
+    "package-lock.json",
+    "yarn.lock",
+    "pnpm-lock.yaml",
+    "requirements.txt",
+    "poetry.lock",
+    "Pipfile.lock",
+    "Cargo.lock",
+    "go.sum",
+    "Gemfile.lock",
+];
+
+/// Files whose changes make the setup step run again.
+pub fn watched_files(project: &ProjectConfig, service: &Service, setup: &Setup) -> Vec<String> {
+    if !setup.watch.is_empty() {
+        return setup.watch.clone();
+    }
+
+    let dir = project.service_dir(service);
+    // Anchored, so lockfiles nested in node_modules and the like don't count
+    DEFAULT_WATCH_FILES
+        .iter()
+        .filter(|file| dir.join(file).is_file())
+        .map(|file| format!("/{}", file))
+        .collect()
+}
+
+/// Whether the service's setup has never run, or its command or watched
+/// files changed since it last succeeded.
+pub fn setup_needed(project: &ProjectConfig, service: &Service, store: &FingerprintStore) -> Result<bool> {
+    let Some(setup) = &service.setup else { return Ok(false) };
+
+    let current = hash_globs(&project.service_dir(service), &setup.command, &watched_files(project, service, setup))?;
+    Ok(store.setup(&service.name) != Some(current.as_str()))
+}
+
+/// Runs the service's setup command when needed (or always with `force`)
+/// and records the new fingerprint once it succeeds. Returns whether it ran.
+pub async fn run_setup(
+    project: &ProjectConfig,
+    service: &Service,
+    env: &ResolvedEnv,
+    store: &mut FingerprintStore,
+    force: bool,
+) -> Result<bool> {
+    let Some(setup) = &service.setup else { return Ok(false) };
+
+    if !force && !setup_needed(project, service, store)? {
+        return Ok(false);
+    }
+
+    let dir = project.service_dir(service);
+    println!("{} {}", "Running setup for".dimmed(), service.name.bold());
+    println!("  {}", env.redactor().redact(&setup.command).dimmed());
+
+    let status = tokio::process::Command::new("sh")
+        .arg("-c")
+        .arg(&setup.command)
+        .current_dir(&dir)
+        .envs(&env.vars)
+        .env("DEVSPIN_PROJECT", &project.name)
+        .env("DEVSPIN_SERVICE", &service.name)
+        .status()
+        .await
+        .map_err(|e| ToolError::ProcessError(format!("Failed to run setup for {}: {}", service.name, e)))?;
+
+    if !status.success() {
+        return Err(ToolError::ProcessError(format!(
+            "Setup for {} failed ({})", service.name, status
+        )));
+    }
+
+    // Hash after the run: setup may rewrite the lockfile it watches
+    let done = hash_globs(&dir, &setup.command, &watched_files(project, service, setup))?;
+    store.set_setup(&service.name, done);
+    store.save()?;
+
+    println!("{} {}", "✓".green(), format!("Setup for {} complete", service.name).bold());
+    Ok(true)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_setup_reruns_when_lockfile_changes() {
+        let dir = TempDir::new().unwrap();
+        std::fs::write(dir.path().join("package-lock.json"), "{}").unwrap();
+        let config_path = dir.path().join("devspin.yaml");
+        std::fs::write(&config_path, r#"
+name: app
+services:
+  - name: web
+    service_type: web
+    command: npm run dev
+    dependencies: []
+    setup_command: npm install
+"#).unwrap();
+
+        let project = ProjectConfig::load(&config_path.to_string_lossy(), None).unwrap();
+        let service = &project.services.as_ref().unwrap()[0];
+        let setup = service.setup.as_ref().unwrap();
+        assert_eq!(watched_files(&project, service, setup), vec!["/package-lock.json"]);
+
+        let mut store = FingerprintStore::default();
+        assert!(setup_needed(&project, service, &store).unwrap());
+
+        let hash = hash_globs(dir.path(), &setup.command, &watched_files(&project, service, setup)).unwrap();
+        store.set_setup("web", hash);
+        assert!(!setup_needed(&project, service, &store).unwrap());
+
+        // A dependency's own lockfile is not the project's
+        std::fs::create_dir_all(dir.path().join("node_modules/left-pad")).unwrap();
+        std::fs::write(dir.path().join("node_modules/left-pad/package-lock.json"), "{}").unwrap();
+        assert!(!setup_needed(&project, service, &store).unwrap());
+
+        std::fs::write(dir.path().join("package-lock.json"), "{\"lockfileVersion\": 3}").unwrap();
+        assert!(setup_needed(&project, service, &store).unwrap());
+    }
+}
@@ -4,14 +4,29 @@ use std::process::Child;
 
 #[derive(Debug, Clone)]
 pub struct ProcessInfo {
+    /// 0 for containers
     pub pid: u32,
+    /// Set for container services, which docker runs instead of a child process
+    pub container_id: Option<String>,
     pub service_name: String,
+    /// Index among the service's replicas (0 when it runs once)
+    pub replica: u32,
     pub project_name: String,
     pub command: String,
     pub start_time: std::time::SystemTime,
     pub status: ProcessStatus,
 }
 
+impl ProcessInfo {
+    /// How the service is identified in output: `PID: 123` or `container: 3f2a9c1d0b7e`.
+    pub fn handle(&self) -> String {
+        match &self.container_id {
+            Some(id) => format!("container: {}", crate::process::containers::short_id(id)),
+            None => format!("PID: {}", self.pid),
+        }
+    }
+}
+
 #[derive(Debug, Clone)]
 pub enum ProcessStatus {
     Running,
@@ -28,19 +43,30 @@ pub struct RunningProcess {
 #[derive(Debug)]
 pub struct ProcessState {
     processes: HashMap<u32, RunningProcess>,
+    /// `docker logs --follow` streams for attached container services
+    log_followers: Vec<Child>,
 }
 
 impl ProcessState {
     pub fn new() -> Self {
         ProcessState {
             processes: HashMap::new(),
+            log_followers: Vec::new(),
         }
     }
 
+    /// Keeps a log stream so it is reaped when its container stops and
+    /// killed when devspin exits.
+    pub fn add_log_follower(&mut self, child: Child) {
+        self.log_followers.retain_mut(|follower| matches!(follower.try_wait(), Ok(None)));
+        self.log_followers.push(child);
+    }
+
     pub fn add_process(
         &mut self,
         child: Child,
         service_name: &str,
+        replica: u32,
         project_name: &str,
         command: &str,
     ) -> Result<(), Box<dyn std::error::Error>> {
@@ -51,7 +77,9 @@ impl ProcessState {
 
         let process_info = ProcessInfo {
             pid,
+            container_id: None,
             service_name: service_name.to_string(),
+            replica,
             project_name: project_name.to_string(),
             command: command.to_string(),
             start_time: std::time::SystemTime::now(),
@@ -93,6 +121,14 @@ impl ProcessState {
         Ok(())
     }
 
+    /// Removes and returns a service's running process, e.g. to restart it.
+    pub fn take_service(&mut self, project_name: &str, service_name: &str) -> Option<RunningProcess> {
+        let pid = self.processes.values().find(|p| {
+            p.info.project_name == project_name && p.info.service_name == service_name
+        })?.info.pid;
+        self.processes.remove(&pid)
+    }
+
     pub fn process_count(&self) -> usize {
         self.processes.len()
     }
@@ -114,6 +150,11 @@ impl Default for ProcessState {
 
 impl Drop for ProcessState {
     fn drop(&mut self) {
+        for mut follower in std::mem::take(&mut self.log_followers) {
+            let _ = follower.kill();
+            let _ = follower.wait();
+        }
+
         if !self.processes.is_empty() {
             eprintln!(
                 "⚠️  Warning: {} processes still running",
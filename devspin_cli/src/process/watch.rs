@@ -0,0 +1,124 @@
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+
+use crate::configs::yaml_parser::{Service, WatchConfig};
+use crate::error::Result;
+use crate::process::fingerprint::matching_files;
+
+/// Polls a service's watched files for changes.
+///
+/// Polling keeps us free of platform-specific notification APIs; the
+/// `ignore` crate already skips `.gitignore`d trees like `node_modules` or
+/// `target`, which keeps each scan cheap.
+pub struct FileWatcher {
+    dir: PathBuf,
+    globs: Vec<String>,
+    snapshot: BTreeMap<PathBuf, (Option<SystemTime>, u64)>,
+}
+
+impl FileWatcher {
+    pub fn new(dir: &Path, config: &WatchConfig) -> Result<Self> {
+        let mut globs = if config.paths.is_empty() {
+            vec!["**".to_string()]
+        } else {
+            config.paths.clone()
+        };
+        globs.extend(config.ignore.iter().map(|glob| format!("!{}", glob)));
+
+        let mut watcher = FileWatcher {
+            dir: dir.to_path_buf(),
+            globs,
+            snapshot: BTreeMap::new(),
+        };
+        watcher.snapshot = watcher.scan()?;
+        Ok(watcher)
+    }
+
+    fn scan(&self) -> Result<BTreeMap<PathBuf, (Option<SystemTime>, u64)>> {
+        Ok(matching_files(&self.dir, &self.globs, true)?
+            .into_iter()
+            .filter_map(|path| {
+                let metadata = std::fs::metadata(&path).ok()?;
+                Some((path, (metadata.modified().ok(), metadata.len())))
+            })
+            .collect())
+    }
+
+    /// Files added, removed or modified since the previous poll.
+    pub fn poll(&mut self) -> Result<Vec<PathBuf>> {
+        let current = self.scan()?;
+
+        let mut changed: Vec<PathBuf> = current
+            .iter()
+            .filter(|(path, stamp)| self.snapshot.get(*path) != Some(stamp))
+            .map(|(path, _)| path.clone())
+            .collect();
+        changed.extend(self.snapshot.keys().filter(|path| !current.contains_key(*path)).cloned());
+
+        self.snapshot = current;
+        Ok(changed)
+    }
+}
+
+/// Services that depend on `name`, directly or transitively, in start order.
+pub fn dependents_of<'a>(services: &'a [Service], name: &str) -> Vec<&'a Service> {
+    let mut affected = vec![name.to_string()];
+    let mut result: Vec<&Service> = Vec::new();
+
+    // Services are few; repeat until no new dependent shows up
+    loop {
+        let next = services.iter().find(|service| {
+            !affected.contains(&service.name)
+                && service.dependencies.iter().any(|dependency| affected.contains(dependency))
+        });
+        match next {
+            Some(service) => {
+                affected.push(service.name.clone());
+                result.push(service);
+            }
+            None => return result,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_poll_reports_changes_outside_ignored_files() {
+        let dir = TempDir::new().unwrap();
+        std::fs::write(dir.path().join(".gitignore"), "build/\n").unwrap();
+        std::fs::write(dir.path().join("main.go"), "package main").unwrap();
+        std::fs::create_dir_all(dir.path().join("build")).unwrap();
+
+        let config: WatchConfig = serde_yaml::from_str("paths: ['**/*.go', '**/*.log']\nignore: '*.log'\n").unwrap();
+        let mut watcher = FileWatcher::new(dir.path(), &config).unwrap();
+        assert!(watcher.poll().unwrap().is_empty());
+
+        std::fs::write(dir.path().join("build/out.go"), "ignored").unwrap();
+        std::fs::write(dir.path().join("debug.log"), "ignored").unwrap();
+        assert!(watcher.poll().unwrap().is_empty());
+
+        std::fs::write(dir.path().join("util.go"), "package main").unwrap();
+        std::fs::remove_file(dir.path().join("main.go")).unwrap();
+        let changed = watcher.poll().unwrap();
+        assert_eq!(changed.len(), 2);
+    }
+
+    #[test]
+    fn test_dependents_are_transitive() {
+        let services: Vec<Service> = serde_yaml::from_str(r#"
+- { name: db, service_type: db, command: db, dependencies: [] }
+- { name: api, service_type: api, command: api, dependencies: [db] }
+- { name: web, service_type: web, command: web, dependencies: [api] }
+- { name: docs, service_type: web, command: docs, dependencies: [] }
+"#).unwrap();
+
+        let names: Vec<&str> = dependents_of(&services, "db").iter().map(|s| s.name.as_str()).collect();
+        assert_eq!(names, vec!["api", "web"]);
+        assert!(dependents_of(&services, "docs").is_empty());
+    }
+}